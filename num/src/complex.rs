@@ -0,0 +1,162 @@
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::Float;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complex<F> {
+    pub re: F,
+    pub im: F,
+}
+
+impl<F> Complex<F>
+where
+    F: Float,
+{
+    pub fn new(re: F, im: F) -> Self {
+        Self { re, im }
+    }
+
+    pub fn from_real(re: F) -> Self {
+        Self { re, im: F::ZERO }
+    }
+
+    // r e^(i theta)
+    pub fn from_polar(r: F, theta: F) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        Self {
+            re: r * cos,
+            im: r * sin,
+        }
+    }
+
+    pub fn conj(self) -> Self {
+        Self {
+            re: self.re,
+            im: -self.im,
+        }
+    }
+
+    pub fn norm_sqr(self) -> F {
+        self.re * self.re + self.im * self.im
+    }
+
+    pub fn norm(self) -> F {
+        // scaled to avoid overflows
+        let scale = self.re.abs().max(self.im.abs());
+        if scale == F::ZERO {
+            return F::ZERO;
+        }
+        let (re, im) = (self.re / scale, self.im / scale);
+        scale * (re * re + im * im).sqrt()
+    }
+
+    pub fn scale(self, k: F) -> Self {
+        Self {
+            re: self.re * k,
+            im: self.im * k,
+        }
+    }
+}
+
+impl<F> Add for Complex<F>
+where
+    F: Float,
+{
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl<F> AddAssign for Complex<F>
+where
+    F: Float,
+{
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<F> Sub for Complex<F>
+where
+    F: Float,
+{
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl<F> SubAssign for Complex<F>
+where
+    F: Float,
+{
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<F> Mul for Complex<F>
+where
+    F: Float,
+{
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl<F> MulAssign for Complex<F>
+where
+    F: Float,
+{
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<F> Div for Complex<F>
+where
+    F: Float,
+{
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let d = other.norm_sqr().recip();
+        Self::new(
+            (self.re * other.re + self.im * other.im) * d,
+            (self.im * other.re - self.re * other.im) * d,
+        )
+    }
+}
+
+impl<F> Neg for Complex<F>
+where
+    F: Float,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl<F> fmt::Display for Complex<F>
+where
+    F: Float + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.im < F::ZERO {
+            write!(f, "{}-{}i", self.re, -self.im)
+        } else {
+            write!(f, "{}+{}i", self.re, self.im)
+        }
+    }
+}
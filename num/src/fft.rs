@@ -0,0 +1,164 @@
+use crate::complex::Complex;
+use crate::Float;
+
+// X_k = sum x_j e^(-2 pi i jk / n), in place by the iterative radix-2
+// Cooley-Tukey algorithm, n should be a power of two
+pub fn fft<F>(x: &mut [Complex<F>])
+where
+    F: Float,
+{
+    transform(x, -F::ONE);
+}
+
+// x_j = 1/n sum X_k e^(2 pi i jk / n), the inverse of `fft`
+pub fn ifft<F>(x: &mut [Complex<F>])
+where
+    F: Float,
+{
+    transform(x, F::ONE);
+
+    let scale = F::from(x.len() as f64).recip();
+    for x in x.iter_mut() {
+        *x = x.scale(scale);
+    }
+}
+
+// X_k = sum x_j e^(-2 pi i jk / n) for any n, by Bluestein's algorithm: with
+// jk = (j^2 + k^2 - (k - j)^2) / 2 the transform is a convolution, computed
+// with power of two FFTs
+pub fn dft<F>(x: &[Complex<F>]) -> Vec<Complex<F>>
+where
+    F: Float,
+{
+    let n = x.len();
+    if n.is_power_of_two() || n == 0 {
+        let mut y = x.to_vec();
+        fft(&mut y);
+        return y;
+    }
+
+    // w_j = e^(-i pi j^2 / n), j^2 is reduced modulo 2n to keep the angle small
+    let chirp = (0..n)
+        .map(|j| {
+            let j2 = (j as u128 * j as u128 % (2 * n) as u128) as f64;
+            Complex::from_polar(F::ONE, -F::PI * F::from(j2) / F::from(n as f64))
+        })
+        .collect::<Vec<_>>();
+
+    let m = (2 * n - 1).next_power_of_two();
+    let mut a = vec![Complex::from_real(F::ZERO); m];
+    for ((a, &x), &w) in a.iter_mut().zip(x).zip(&chirp) {
+        *a = x * w;
+    }
+    // conj(w) at lags -(n - 1)..n, wrapped around
+    let mut b = vec![Complex::from_real(F::ZERO); m];
+    b[0] = chirp[0].conj();
+    for j in 1..n {
+        b[j] = chirp[j].conj();
+        b[m - j] = chirp[j].conj();
+    }
+
+    fft(&mut a);
+    fft(&mut b);
+    for (a, b) in a.iter_mut().zip(&b) {
+        *a *= *b;
+    }
+    ifft(&mut a);
+
+    a.iter().zip(&chirp).map(|(&a, &w)| a * w).collect()
+}
+
+fn transform<F>(x: &mut [Complex<F>], sign: F)
+where
+    F: Float,
+{
+    let n = x.len();
+    if n <= 1 {
+        return;
+    }
+    assert!(n.is_power_of_two(), "the length should be a power of two");
+
+    // bit-reversal permutation
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            x.swap(i, j);
+        }
+    }
+
+    // e^(sign 2 pi i k / n), computed once so that the error does not
+    // accumulate along the stages
+    let twiddles = (0..n / 2)
+        .map(|k| {
+            let theta = sign * F::from(2.) * F::PI * F::from(k as f64) / F::from(n as f64);
+            Complex::from_polar(F::ONE, theta)
+        })
+        .collect::<Vec<_>>();
+
+    let mut len = 2;
+    while len <= n {
+        let stride = n / len;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let a = x[start + k];
+                let b = x[start + k + len / 2] * twiddles[k * stride];
+                x[start + k] = a + b;
+                x[start + k + len / 2] = a - b;
+            }
+        }
+        len *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_dft(x: &[Complex<f64>]) -> Vec<Complex<f64>> {
+        let n = x.len();
+        (0..n)
+            .map(|k| {
+                let mut sum = Complex::from_real(0.);
+                for (j, &x) in x.iter().enumerate() {
+                    let theta = -2. * core::f64::consts::PI * (j * k) as f64 / n as f64;
+                    sum += x * Complex::from_polar(1., theta);
+                }
+                sum
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fft_matches_dft() {
+        for n in [1, 2, 4, 8, 64] {
+            let x = (0..n)
+                .map(|j| Complex::new((j as f64 * 0.7).sin(), (j as f64).sqrt() - 1.))
+                .collect::<Vec<_>>();
+
+            let mut y = x.clone();
+            fft(&mut y);
+            for (y, e) in y.iter().zip(naive_dft(&x)) {
+                assert!((*y - e).norm() < 1e-10);
+            }
+
+            ifft(&mut y);
+            for (y, x) in y.iter().zip(&x) {
+                assert!((*y - *x).norm() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn bluestein_matches_dft() {
+        for n in [3, 5, 12, 100] {
+            let x = (0..n)
+                .map(|j| Complex::new((j as f64 * 1.3).cos(), (j as f64 * 0.4).sin()))
+                .collect::<Vec<_>>();
+
+            for (y, e) in dft(&x).iter().zip(naive_dft(&x)) {
+                assert!((*y - e).norm() < 1e-10);
+            }
+        }
+    }
+}
@@ -1,14 +1,23 @@
 use core::cmp::Ordering;
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
+pub mod complex;
+pub mod fft;
+pub mod linalg;
+pub mod optimize;
+pub mod poly;
+pub mod special;
+
 pub trait IntoFloat {
     fn into_f32(self) -> f32;
     fn into_f64(self) -> f64;
 }
 
 pub trait Float:
-    Sized
+    IntoFloat
+    + Sized
     + Copy
+    + PartialOrd
     + Neg<Output = Self>
     + Add<Output = Self>
     + AddAssign
@@ -27,14 +36,20 @@ pub trait Float:
     const ZERO: Self;
     const ONE: Self;
     const PI: Self;
+    const INFINITY: Self;
+    const NEG_INFINITY: Self;
+    const EPSILON: Self;
 
     fn sqrt(self) -> Self;
     fn powf(self, n: Self) -> Self;
     fn recip(self) -> Self;
     fn ln(self) -> Self;
+    fn ln_1p(self) -> Self;
+    fn exp(self) -> Self;
     fn sin_cos(self) -> (Self, Self);
     fn mul_add(self, a: Self, b: Self) -> Self;
     fn abs(self) -> Self;
+    fn floor(self) -> Self;
 
     fn max(self, other: Self) -> Self;
     fn min(self, other: Self) -> Self;
@@ -62,6 +77,9 @@ macro_rules! float_impl {
             const ZERO: Self = 0.;
             const ONE: Self = 1.;
             const PI: Self = core::$fty::consts::PI;
+            const INFINITY: Self = $fty::INFINITY;
+            const NEG_INFINITY: Self = $fty::NEG_INFINITY;
+            const EPSILON: Self = $fty::EPSILON;
 
             fn sqrt(self) -> Self {
                 self.sqrt()
@@ -79,6 +97,14 @@ macro_rules! float_impl {
                 self.ln()
             }
 
+            fn ln_1p(self) -> Self {
+                self.ln_1p()
+            }
+
+            fn exp(self) -> Self {
+                self.exp()
+            }
+
             fn sin_cos(self) -> (Self, Self) {
                 self.sin_cos()
             }
@@ -91,6 +117,10 @@ macro_rules! float_impl {
                 self.abs()
             }
 
+            fn floor(self) -> Self {
+                self.floor()
+            }
+
             fn max(self, other: Self) -> Self {
                 self.max(other)
             }
@@ -0,0 +1,363 @@
+use core::fmt;
+use core::ops::{Index, IndexMut};
+
+use crate::Float;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinalgError {
+    DimensionMismatch,
+    NotCorrelation,
+    NotPositiveDefinite,
+    NotSymmetric,
+    Singular,
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DimensionMismatch => write!(f, "the matrix dimensions do not match"),
+            Self::NotCorrelation => write!(f, "the matrix does not have a unit diagonal"),
+            Self::NotPositiveDefinite => write!(f, "the matrix is not positive definite"),
+            Self::NotSymmetric => write!(f, "the matrix is not symmetric"),
+            Self::Singular => write!(f, "the matrix is singular"),
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {}
+
+// dense row-major matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<F> {
+    rows: usize,
+    cols: usize,
+    data: Vec<F>,
+}
+
+impl<F> Matrix<F>
+where
+    F: Float,
+{
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![F::ZERO; rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::zeros(n, n);
+        for i in 0..n {
+            m[(i, i)] = F::ONE;
+        }
+        m
+    }
+
+    pub fn from_rows<const C: usize>(rows: &[[F; C]]) -> Self {
+        Self {
+            rows: rows.len(),
+            cols: C,
+            data: rows.iter().flatten().copied().collect(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    // up to rounding, relative to the largest entry
+    pub fn is_symmetric(&self) -> bool {
+        let tol = F::from(self.rows as f64) * F::EPSILON * self.max_abs();
+        self.is_square()
+            && (0..self.rows).all(|i| (0..i).all(|j| (self[(i, j)] - self[(j, i)]).abs() <= tol))
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut t = Self::zeros(self.cols, self.rows);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                t[(j, i)] = self[(i, j)];
+            }
+        }
+        t
+    }
+
+    pub fn mul(&self, other: &Self) -> Result<Self, LinalgError> {
+        if self.cols != other.rows {
+            return Err(LinalgError::DimensionMismatch);
+        }
+
+        let mut m = Self::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)];
+                for j in 0..other.cols {
+                    m[(i, j)] += a * other[(k, j)];
+                }
+            }
+        }
+        Ok(m)
+    }
+
+    pub fn mul_vec(&self, x: &[F]) -> Result<Vec<F>, LinalgError> {
+        if self.cols != x.len() {
+            return Err(LinalgError::DimensionMismatch);
+        }
+
+        let mut y = vec![F::ZERO; self.rows];
+        for (i, y) in y.iter_mut().enumerate() {
+            for (j, &x) in x.iter().enumerate() {
+                *y += self[(i, j)] * x;
+            }
+        }
+        Ok(y)
+    }
+
+    fn max_abs(&self) -> F {
+        self.data.iter().fold(F::ZERO, |m, x| m.max(x.abs()))
+    }
+
+    // lower triangular L with A = L L^T, only the lower triangle of A is read
+    pub fn cholesky(&self) -> Result<Self, LinalgError> {
+        self.cholesky_with(None)
+    }
+
+    // like `cholesky`, but also accepts positive semidefinite matrices, the
+    // columns of L with a (numerically) zero pivot are left as zero
+    pub fn cholesky_semidefinite(&self) -> Result<Self, LinalgError> {
+        let tol = F::from(self.rows as f64) * F::EPSILON * self.max_abs();
+        self.cholesky_with(Some(tol))
+    }
+
+    fn cholesky_with(&self, tol: Option<F>) -> Result<Self, LinalgError> {
+        if !self.is_square() {
+            return Err(LinalgError::DimensionMismatch);
+        }
+
+        let n = self.rows;
+        let mut l = Self::zeros(n, n);
+        for j in 0..n {
+            let mut d = self[(j, j)];
+            for k in 0..j {
+                d -= l[(j, k)] * l[(j, k)];
+            }
+            if let Some(tol) = tol {
+                if d.abs() <= tol {
+                    continue;
+                }
+            }
+            // also rejects NaN
+            if d.partial_cmp(&F::ZERO) != Some(core::cmp::Ordering::Greater) {
+                return Err(LinalgError::NotPositiveDefinite);
+            }
+            let d = d.sqrt();
+            l[(j, j)] = d;
+
+            for i in j + 1..n {
+                let mut s = self[(i, j)];
+                for k in 0..j {
+                    s -= l[(i, k)] * l[(j, k)];
+                }
+                l[(i, j)] = s / d;
+            }
+        }
+        Ok(l)
+    }
+
+    // solves A x = b by LU decomposition with partial pivoting
+    pub fn solve(&self, b: &[F]) -> Result<Vec<F>, LinalgError> {
+        if !self.is_square() || b.len() != self.rows {
+            return Err(LinalgError::DimensionMismatch);
+        }
+
+        let n = self.rows;
+        let tol = F::from(n as f64) * F::EPSILON * self.max_abs();
+        let mut a = self.clone();
+        let mut x = b.to_vec();
+
+        for k in 0..n {
+            let mut pivot = k;
+            for i in k + 1..n {
+                if a[(i, k)].abs() > a[(pivot, k)].abs() {
+                    pivot = i;
+                }
+            }
+            if a[(pivot, k)].abs().partial_cmp(&tol) != Some(core::cmp::Ordering::Greater) {
+                return Err(LinalgError::Singular);
+            }
+            if pivot != k {
+                for j in 0..n {
+                    let t = a[(k, j)];
+                    a[(k, j)] = a[(pivot, j)];
+                    a[(pivot, j)] = t;
+                }
+                x.swap(k, pivot);
+            }
+
+            for i in k + 1..n {
+                let factor = a[(i, k)] / a[(k, k)];
+                for j in k..n {
+                    let t = a[(k, j)];
+                    a[(i, j)] -= factor * t;
+                }
+                let t = x[k];
+                x[i] -= factor * t;
+            }
+        }
+
+        for k in (0..n).rev() {
+            let mut sum = x[k];
+            for j in k + 1..n {
+                sum -= a[(k, j)] * x[j];
+            }
+            x[k] = sum / a[(k, k)];
+        }
+        Ok(x)
+    }
+
+    // column by column with `solve`
+    pub fn inverse(&self) -> Result<Self, LinalgError> {
+        let n = self.rows;
+        let mut inverse = Self::zeros(n, self.cols);
+        let mut e = vec![F::ZERO; n];
+        for j in 0..self.cols {
+            e[j] = F::ONE;
+            let column = self.solve(&e)?;
+            e[j] = F::ZERO;
+            for (i, x) in column.into_iter().enumerate() {
+                inverse[(i, j)] = x;
+            }
+        }
+        Ok(inverse)
+    }
+}
+
+pub struct Levinson<F> {
+    // phi_1..phi_n of the best linear predictor of order n
+    pub coefficients: Vec<F>,
+    // phi_kk for k = 1..=n, the partial autocorrelations
+    pub reflection: Vec<F>,
+    // variance of the one-step prediction error
+    pub variance: F,
+}
+
+// Levinson-Durbin recursion, solves the Toeplitz system of the Yule-Walker
+// equations for r[0..=n] (autocovariances of lags 0 to n)
+pub fn levinson<F>(r: &[F]) -> Levinson<F>
+where
+    F: Float,
+{
+    let n = r.len() - 1;
+    let mut phi: Vec<F> = Vec::with_capacity(n);
+    let mut reflection = Vec::with_capacity(n);
+    let mut variance = r[0];
+
+    for k in 1..=n {
+        let mut num = r[k];
+        for j in 1..k {
+            num -= phi[j - 1] * r[k - j];
+        }
+        let kappa = num / variance;
+
+        let previous = phi.clone();
+        for j in 1..k {
+            phi[j - 1] = previous[j - 1] - kappa * previous[k - j - 1];
+        }
+        phi.push(kappa);
+
+        reflection.push(kappa);
+        variance *= F::ONE - kappa * kappa;
+    }
+
+    Levinson {
+        coefficients: phi,
+        reflection,
+        variance,
+    }
+}
+
+impl<F> Index<(usize, usize)> for Matrix<F> {
+    type Output = F;
+
+    fn index(&self, (i, j): (usize, usize)) -> &F {
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<F> IndexMut<(usize, usize)> for Matrix<F> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut F {
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cholesky_works() {
+        let a = Matrix::from_rows(&[[4., 12., -16.], [12., 37., -43.], [-16., -43., 98.]]);
+        let l = a.cholesky().unwrap();
+
+        assert_eq!(
+            l,
+            Matrix::from_rows(&[[2., 0., 0.], [6., 1., 0.], [-8., 5., 3.]]),
+        );
+        assert_eq!(l.mul(&l.transpose()).unwrap(), a);
+    }
+
+    #[test]
+    fn cholesky_semidefinite_works() {
+        // rank one
+        let a = Matrix::from_rows(&[[1., 2.], [2., 4.]]);
+        assert_eq!(a.cholesky(), Err(LinalgError::NotPositiveDefinite));
+
+        let l = a.cholesky_semidefinite().unwrap();
+        assert_eq!(l, Matrix::from_rows(&[[1., 0.], [2., 0.]]));
+    }
+
+    #[test]
+    fn inverse_works() {
+        let a = Matrix::from_rows(&[[4., 7.], [2., 6.]]);
+        let inverse = a.inverse().unwrap();
+        let expected = Matrix::from_rows(&[[0.6, -0.7], [-0.2, 0.4]]);
+        for i in 0..2 {
+            for j in 0..2 {
+                assert!((inverse[(i, j)] - expected[(i, j)]).abs() < 1e-12);
+            }
+        }
+
+        let singular = Matrix::from_rows(&[[1., 2.], [2., 4.]]);
+        assert_eq!(singular.inverse(), Err(LinalgError::Singular));
+    }
+
+    #[test]
+    fn solve_works() {
+        let a = Matrix::from_rows(&[[0., 2., 1.], [1., -1., 0.], [3., 0., 4.]]);
+        let x = a.solve(&[7., -1., 15.]).unwrap();
+        for (x, e) in x.iter().zip([1., 2., 3.]) {
+            assert!((x - e).abs() < 1e-12);
+        }
+
+        let a = Matrix::from_rows(&[[1., 2.], [2., 4.]]);
+        assert_eq!(a.solve(&[1., 1.]), Err(LinalgError::Singular));
+    }
+
+    #[test]
+    fn cholesky_rejects_indefinite() {
+        let a = Matrix::from_rows(&[[1., 2.], [2., 1.]]);
+        assert_eq!(a.cholesky(), Err(LinalgError::NotPositiveDefinite));
+
+        let a = Matrix::<f64>::zeros(2, 3);
+        assert_eq!(a.cholesky(), Err(LinalgError::DimensionMismatch));
+    }
+}
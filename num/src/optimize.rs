@@ -0,0 +1,184 @@
+use crate::Float;
+
+const MAX_ITERATIONS: usize = 200;
+
+// maximizes `f` over [a, b], assuming it is unimodal there
+pub fn golden_section_max<F, G>(mut f: G, mut a: F, mut b: F, tol: F) -> F
+where
+    F: Float,
+    G: FnMut(F) -> F,
+{
+    // 1 / golden ratio
+    let r = F::from((5_f64.sqrt() - 1.) / 2.);
+
+    let mut c = b - r * (b - a);
+    let mut d = a + r * (b - a);
+    let mut fc = f(c);
+    let mut fd = f(d);
+
+    for _ in 0..MAX_ITERATIONS {
+        if (b - a).abs() < tol {
+            break;
+        }
+
+        if fc > fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - r * (b - a);
+            fc = f(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + r * (b - a);
+            fd = f(d);
+        }
+    }
+
+    (a + b) / F::from(2.)
+}
+
+// finds a root of `f` in [a, b], f(a) and f(b) should have opposite signs
+pub fn bisect<F, G>(mut f: G, mut a: F, mut b: F, tol: F) -> F
+where
+    F: Float,
+    G: FnMut(F) -> F,
+{
+    let mut fa = f(a);
+
+    for _ in 0..MAX_ITERATIONS {
+        let m = (a + b) / F::from(2.);
+        if (b - a).abs() < tol {
+            return m;
+        }
+
+        let fm = f(m);
+        if (fm < F::ZERO) == (fa < F::ZERO) {
+            a = m;
+            fa = fm;
+        } else {
+            b = m;
+        }
+    }
+
+    (a + b) / F::from(2.)
+}
+
+// minimizes `f` with the Nelder-Mead simplex method, starting from a simplex
+// around `x0` with edges of length `step`. stops when the values at the
+// vertices are within `tol` of each other
+pub fn nelder_mead<F, G>(mut f: G, x0: &[F], step: F, tol: F, max_iterations: usize) -> Vec<F>
+where
+    F: Float,
+    G: FnMut(&[F]) -> F,
+{
+    let n = x0.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let half = F::from(0.5);
+    let two = F::from(2.);
+
+    let mut simplex = vec![x0.to_vec()];
+    for i in 0..n {
+        let mut x = x0.to_vec();
+        x[i] += step;
+        simplex.push(x);
+    }
+    let mut values = simplex.iter().map(|x| f(x)).collect::<Vec<_>>();
+
+    // x0 + t (x1 - x0)
+    let along = |x0: &[F], x1: &[F], t: F| {
+        x0.iter()
+            .zip(x1)
+            .map(|(&a, &b)| a + t * (b - a))
+            .collect::<Vec<_>>()
+    };
+
+    for _ in 0..max_iterations {
+        let mut order = (0..=n).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+        simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+        values = order.iter().map(|&i| values[i]).collect();
+
+        if (values[n] - values[0]).abs() <= tol * (F::ONE + values[0].abs()) {
+            break;
+        }
+
+        let mut centroid = vec![F::ZERO; n];
+        for x in &simplex[..n] {
+            for (c, &x) in centroid.iter_mut().zip(x) {
+                *c += x / F::from(n as f64);
+            }
+        }
+
+        let reflected = along(&centroid, &simplex[n], -F::ONE);
+        let fr = f(&reflected);
+        if fr < values[0] {
+            let expanded = along(&centroid, &simplex[n], -two);
+            let fe = f(&expanded);
+            if fe < fr {
+                simplex[n] = expanded;
+                values[n] = fe;
+            } else {
+                simplex[n] = reflected;
+                values[n] = fr;
+            }
+        } else if fr < values[n - 1] {
+            simplex[n] = reflected;
+            values[n] = fr;
+        } else {
+            // outside contraction when the reflection improved the worst
+            // vertex, inside contraction otherwise
+            let (contracted, bound) = if fr < values[n] {
+                (along(&centroid, &simplex[n], -half), fr)
+            } else {
+                (along(&centroid, &simplex[n], half), values[n])
+            };
+            let fc = f(&contracted);
+
+            if fc < bound {
+                simplex[n] = contracted;
+                values[n] = fc;
+            } else {
+                // shrink towards the best vertex
+                for i in 1..=n {
+                    simplex[i] = along(&simplex[0], &simplex[i], half);
+                    values[i] = f(&simplex[i]);
+                }
+            }
+        }
+    }
+
+    let best = (0..=n)
+        .min_by(|&i, &j| values[i].total_cmp(&values[j]))
+        .expect("the simplex should not be empty");
+    simplex.swap_remove(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_section_works() {
+        let x = golden_section_max(|x: f64| -(x - 1.5) * (x - 1.5), -10., 10., 1e-10);
+        assert!((x - 1.5).abs() < 1e-8);
+    }
+
+    #[test]
+    fn bisect_works() {
+        let x = bisect(|x: f64| x * x - 2., 0., 2., 1e-14);
+        assert!((x - 2_f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn nelder_mead_works() {
+        // Rosenbrock
+        let rosenbrock = |x: &[f64]| (1. - x[0]).powi(2) + 100. * (x[1] - x[0] * x[0]).powi(2);
+        let x = nelder_mead(rosenbrock, &[-1.2, 1.], 0.5, 1e-15, 5000);
+        assert!((x[0] - 1.).abs() < 1e-5 && (x[1] - 1.).abs() < 1e-5);
+    }
+}
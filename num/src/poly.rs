@@ -0,0 +1,152 @@
+use crate::complex::Complex;
+use crate::Float;
+
+const MAX_ITERATIONS: usize = 1000;
+
+// roots of c[0] + c[1] z + ... + c[n] z^n by the Durand-Kerner method,
+// trailing zero coefficients are dropped
+pub fn roots<F>(c: &[F]) -> Vec<Complex<F>>
+where
+    F: Float,
+{
+    let n = match c.iter().rposition(|&c| c != F::ZERO) {
+        Some(n) => n,
+        None => return Vec::new(),
+    };
+    if n == 0 {
+        return Vec::new();
+    }
+
+    // monic
+    let lead = c[n];
+    let a = c[..n].iter().map(|&c| c / lead).collect::<Vec<_>>();
+
+    // Cauchy's bound on the magnitude of the roots
+    let radius = F::ONE + a.iter().fold(F::ZERO, |m, c| m.max(c.abs()));
+
+    // initial guesses on a circle, avoiding symmetric placements
+    let seed = Complex::new(F::from(0.4), F::from(0.9));
+    let mut z = Vec::with_capacity(n);
+    let mut w = Complex::from_real(F::ONE);
+    for _ in 0..n {
+        w *= seed;
+        z.push(w.scale(radius / w.norm()));
+    }
+
+    let eval = |x: Complex<F>| {
+        let mut y = Complex::from_real(F::ONE);
+        for &c in a.iter().rev() {
+            y = y * x + Complex::from_real(c);
+        }
+        y
+    };
+
+    let tol = F::from(16.) * F::EPSILON;
+    for _ in 0..MAX_ITERATIONS {
+        let mut change = F::ZERO;
+        for i in 0..n {
+            let mut d = Complex::from_real(F::ONE);
+            for j in 0..n {
+                if i != j {
+                    d *= z[i] - z[j];
+                }
+            }
+            let delta = eval(z[i]) / d;
+            z[i] -= delta;
+            change = change.max(delta.norm() / z[i].norm().max(F::ONE));
+        }
+        if change < tol {
+            break;
+        }
+    }
+    z
+}
+
+// whether every root of 1 + c[0] z + ... + c[n-1] z^n lies strictly outside
+// the unit circle, by the Schur-Cohn (step-down) test: the polynomial is
+// written as 1 - a_1 z - ... - a_n z^n and the reflection coefficients of
+// the Levinson recursion are computed backwards, all of them should be
+// smaller than one in magnitude
+pub fn roots_outside_unit_circle<F>(c: &[F]) -> bool
+where
+    F: Float,
+{
+    let mut a = c.iter().map(|&c| -c).collect::<Vec<_>>();
+    while let Some(&kappa) = a.last() {
+        let k = a.len();
+        if kappa.abs() >= F::ONE {
+            return false;
+        }
+        let d = F::ONE - kappa * kappa;
+        let next = (0..k - 1)
+            .map(|j| (a[j] + kappa * a[k - 2 - j]) / d)
+            .collect();
+        a = next;
+    }
+    true
+}
+
+// coefficients of the product of a[0] + a[1] z + ... and b[0] + b[1] z + ...
+pub fn mul<F>(a: &[F], b: &[F]) -> Vec<F>
+where
+    F: Float,
+{
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut c = vec![F::ZERO; a.len() + b.len() - 1];
+    for (i, &a) in a.iter().enumerate() {
+        for (j, &b) in b.iter().enumerate() {
+            c[i + j] += a * b;
+        }
+    }
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roots_works() {
+        // (z - 1)(z - 2)(z^2 + 1) = z^4 - 3z^3 + 3z^2 - 3z + 2
+        let mut r = roots(&[2., -3., 3., -3., 1., 0.]);
+        r.sort_by(|x, y| x.re.total_cmp(&y.re).then(x.im.total_cmp(&y.im)));
+
+        let expected = [(0., -1.), (0., 1.), (1., 0.), (2., 0.)];
+        assert_eq!(r.len(), expected.len());
+        for (r, (re, im)) in r.iter().zip(expected) {
+            assert!((r.re - re).abs() < 1e-9 && (r.im - im).abs() < 1e-9);
+        }
+
+        assert!(roots(&[3.]).is_empty());
+    }
+
+    #[test]
+    fn mul_works() {
+        // (1 - z)(1 + z + z^2) = 1 - z^3
+        assert_eq!(mul(&[1., -1.], &[1., 1., 1.]), vec![1., 0., 0., -1.]);
+    }
+
+    #[test]
+    fn schur_cohn_works() {
+        // 1 - 0.7z, root 1/0.7
+        assert!(roots_outside_unit_circle(&[-0.7]));
+        // 1 - 1.2z, root 1/1.2
+        assert!(!roots_outside_unit_circle(&[-1.2]));
+        // 1 - 0.5z - 0.6z^2, roots 0.94 and -1.77
+        assert!(!roots_outside_unit_circle(&[-0.5, -0.6]));
+        // 1 - 0.5z + 0.3z^2, complex roots of magnitude 1.83
+        assert!(roots_outside_unit_circle(&[-0.5, 0.3]));
+        assert!(roots_outside_unit_circle::<f64>(&[]));
+
+        // agrees with the roots
+        for c in [[0.3, -0.8, 0.1], [1.1, 0.2, -0.4], [-0.2, 0.9, 0.5]] {
+            let mut full = vec![1.];
+            full.extend(c);
+            let outside = roots(&full).iter().all(|r| r.norm() > 1.);
+            assert_eq!(roots_outside_unit_circle(&c), outside);
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use crate::Float;
+
+const MAX_ITERATIONS: usize = 1000;
+
+// Lanczos approximation, g = 7, n = 9
+const LANCZOS_G: f64 = 7.;
+const LANCZOS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+// ln(gamma(x)) for x > 0
+pub fn ln_gamma<F>(x: F) -> F
+where
+    F: Float,
+{
+    if x < F::from(0.5) {
+        // gamma(x) = gamma(x + 1) / x
+        return ln_gamma(x + F::ONE) - x.ln();
+    }
+
+    let x = x - F::ONE;
+    let t = x + F::from(LANCZOS_G + 0.5);
+
+    let mut a = F::from(LANCZOS[0]);
+    for (i, &c) in LANCZOS.iter().enumerate().skip(1) {
+        a += F::from(c) / (x + F::from(i as f64));
+    }
+
+    F::from(0.5) * (F::from(2.) * F::PI).ln() + (x + F::from(0.5)) * t.ln() - t + a.ln()
+}
+
+// psi(x) = d/dx ln(gamma(x)) for x > 0
+pub fn digamma<F>(mut x: F) -> F
+where
+    F: Float,
+{
+    // psi(x) = psi(x + 1) - 1/x, until the asymptotic expansion is accurate
+    let mut result = F::ZERO;
+    while x < F::from(10.) {
+        result -= x.recip();
+        x += F::ONE;
+    }
+
+    let r = x.recip();
+    let r2 = r * r;
+    let series = r2
+        * (F::from(1. / 12.)
+            - r2 * (F::from(1. / 120.)
+                - r2 * (F::from(1. / 252.) - r2 * (F::from(1. / 240.) - r2 * F::from(1. / 132.)))));
+
+    result + x.ln() - F::from(0.5) * r - series
+}
+
+// regularized lower incomplete gamma function P(a, x)
+pub fn gamma_p<F>(a: F, x: F) -> F
+where
+    F: Float,
+{
+    if x <= F::ZERO {
+        F::ZERO
+    } else if x < a + F::ONE {
+        gamma_series(a, x)
+    } else {
+        F::ONE - gamma_continued_fraction(a, x)
+    }
+}
+
+// regularized upper incomplete gamma function Q(a, x) = 1 - P(a, x)
+pub fn gamma_q<F>(a: F, x: F) -> F
+where
+    F: Float,
+{
+    if x <= F::ZERO {
+        F::ONE
+    } else if x < a + F::ONE {
+        F::ONE - gamma_series(a, x)
+    } else {
+        gamma_continued_fraction(a, x)
+    }
+}
+
+fn gamma_prefactor<F>(a: F, x: F) -> F
+where
+    F: Float,
+{
+    (a * x.ln() - x - ln_gamma(a)).exp()
+}
+
+fn gamma_series<F>(a: F, x: F) -> F
+where
+    F: Float,
+{
+    let mut ap = a;
+    let mut term = a.recip();
+    let mut sum = term;
+    for _ in 0..MAX_ITERATIONS {
+        ap += F::ONE;
+        term *= x / ap;
+        sum += term;
+        if term.abs() < sum.abs() * F::EPSILON {
+            break;
+        }
+    }
+    sum * gamma_prefactor(a, x)
+}
+
+// modified Lentz's method
+fn gamma_continued_fraction<F>(a: F, x: F) -> F
+where
+    F: Float,
+{
+    let tiny = F::from(1e-30);
+
+    let mut b = x + F::ONE - a;
+    let mut c = tiny.recip();
+    let mut d = b.recip();
+    let mut h = d;
+    for i in 1..MAX_ITERATIONS {
+        let i = F::from(i as f64);
+        let an = -i * (i - a);
+        b += F::from(2.);
+        d = an * d + b;
+        if d.abs() < tiny {
+            d = tiny;
+        }
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        d = d.recip();
+        let delta = d * c;
+        h *= delta;
+        if (delta - F::ONE).abs() < F::EPSILON {
+            break;
+        }
+    }
+    h * gamma_prefactor(a, x)
+}
+
+pub fn erf<F>(x: F) -> F
+where
+    F: Float,
+{
+    let p = gamma_p(F::from(0.5), x * x);
+    if x < F::ZERO {
+        -p
+    } else {
+        p
+    }
+}
+
+pub fn erfc<F>(x: F) -> F
+where
+    F: Float,
+{
+    if x < F::ZERO {
+        F::ONE + gamma_p(F::from(0.5), x * x)
+    } else {
+        gamma_q(F::from(0.5), x * x)
+    }
+}
+
+// cumulative distribution function of the standard normal distribution
+pub fn normal_cdf<F>(z: F) -> F
+where
+    F: Float,
+{
+    F::from(0.5) * erfc(-z / F::from(2.).sqrt())
+}
+
+// inverse of the standard normal CDF, Acklam's rational approximation
+// refined with one step of Halley's method
+pub fn normal_quantile<F>(p: F) -> F
+where
+    F: Float,
+{
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+
+    if p <= F::ZERO {
+        return F::NEG_INFINITY;
+    }
+    if p >= F::ONE {
+        return F::INFINITY;
+    }
+
+    let poly = |c: &[f64], x: F| c.iter().fold(F::ZERO, |acc, &c| acc * x + F::from(c));
+
+    let low = F::from(0.02425);
+    let x = if p < low || p > F::ONE - low {
+        let tail = if p < low { p } else { F::ONE - p };
+        let q = (F::from(-2.) * tail.ln()).sqrt();
+        let x = poly(&C, q) / (poly(&D, q) * q + F::ONE);
+        if p < low {
+            x
+        } else {
+            -x
+        }
+    } else {
+        let q = p - F::from(0.5);
+        let r = q * q;
+        poly(&A, r) * q / (poly(&B, r) * r + F::ONE)
+    };
+
+    // Halley's method on normal_cdf(x) - p
+    let e = normal_cdf(x) - p;
+    let u = e * (F::from(2.) * F::PI).sqrt() * (x * x / F::from(2.)).exp();
+    x - u / (F::ONE + x * u / F::from(2.))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(x: f64, y: f64) {
+        assert!((x - y).abs() < 1e-12 * y.abs().max(1.), "{x} != {y}");
+    }
+
+    #[test]
+    fn ln_gamma_works() {
+        assert_close(ln_gamma(1.), 0.);
+        assert_close(ln_gamma(2.), 0.);
+        assert_close(ln_gamma(5.), 24_f64.ln());
+        assert_close(ln_gamma(0.5), core::f64::consts::PI.sqrt().ln());
+        assert_close(ln_gamma(0.1), 2.252_712_651_734_206);
+        assert_close(ln_gamma(100.), 359.134_205_369_575_4);
+    }
+
+    #[test]
+    fn digamma_works() {
+        let euler = 0.577_215_664_901_532_9;
+        assert_close(digamma(1.), -euler);
+        assert_close(digamma(0.5), -euler - 2. * 2_f64.ln());
+        assert_close(digamma(10.), 2.251_752_589_066_721);
+    }
+
+    #[test]
+    fn incomplete_gamma_works() {
+        // P(1, x) = 1 - e^(-x)
+        for x in [0.1, 1., 2.5, 10.] {
+            assert_close(gamma_p(1., x), 1. - f64::exp(-x));
+        }
+        assert_close(gamma_p(3., 2.) + gamma_q(3., 2.), 1.);
+        assert_close(gamma_q(3., 2.), 5. * (-2_f64).exp());
+    }
+
+    #[test]
+    fn erf_works() {
+        assert_close(erf(0.5), 0.520_499_877_813_046_5);
+        assert_close(erf(-1.), -0.842_700_792_949_714_9);
+        assert_close(erfc(2.), 0.004_677_734_981_047_266);
+        assert_close(normal_cdf(1.96), 0.975_002_104_851_780);
+    }
+
+    #[test]
+    fn normal_quantile_works() {
+        for p in [1e-10, 0.001, 0.025, 0.3, 0.5, 0.8, 0.975, 0.9999] {
+            assert_close(normal_cdf(normal_quantile(p)), p);
+        }
+        assert_close(normal_quantile(0.975), 1.959_963_984_540_054);
+    }
+}
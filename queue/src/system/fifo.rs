@@ -2,6 +2,8 @@ use core::cmp::{Ordering, Reverse};
 use std::collections::BinaryHeap;
 
 use num::Float;
+use rand::rngs::Rng;
+use rand::series::ArrivalProcess;
 
 use super::{Client, QueueSystem};
 
@@ -71,6 +73,38 @@ where
             serving_times,
         }
     }
+
+    // the clients of a batch arrive one after the other at the same instant
+    pub fn with_arrivals<A, R>(
+        mut arrivals: A,
+        mut rng: R,
+        serving_times: [Box<dyn FnMut() -> F>; M],
+    ) -> Self
+    where
+        F: 'static,
+        A: ArrivalProcess<F> + 'static,
+        R: Rng + 'static,
+    {
+        let mut pending = 0;
+        let arrival_interval = move || {
+            if pending > 0 {
+                pending -= 1;
+                return F::ZERO;
+            }
+            // empty batches are skipped, their interval adding to the next one
+            let mut interval = F::ZERO;
+            loop {
+                let (next, batch) = arrivals.next_batch(&mut rng);
+                interval += next;
+                if batch > 0 {
+                    pending = batch - 1;
+                    return interval;
+                }
+            }
+        };
+
+        Self::new(Box::new(arrival_interval), serving_times)
+    }
 }
 
 impl<F, const M: usize> QueueSystem<F> for Fifo<F, M>
@@ -103,3 +137,47 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    // replays the given batches
+    struct Batches(std::vec::IntoIter<(f64, usize)>);
+
+    impl ArrivalProcess<f64> for Batches {
+        fn next_batch<R>(&mut self, _rng: &mut R) -> (f64, usize)
+        where
+            R: Rng + ?Sized,
+        {
+            self.0.next().expect("there should be enough batches")
+        }
+    }
+
+    #[test]
+    fn batches_arrive_together() {
+        let batches = vec![(1., 2), (0.5, 0), (0.25, 0), (0.25, 3), (1., 1)];
+        let arrivals = Batches(batches.into_iter());
+        let rng = Xoshiro256PlusPlus::new(SEED);
+        let mut fifo = Fifo::with_arrivals(arrivals, rng, [Box::new(|| 0.5)]);
+
+        let clients = (0..6).map(|_| fifo.step()).collect::<Vec<_>>();
+        let arrivals = clients
+            .iter()
+            .map(|c| c.arrival_instant)
+            .collect::<Vec<_>>();
+        assert_eq!(arrivals, [1., 1., 2., 2., 2., 3.]);
+
+        // the clients of a batch queue for the single server
+        let serves = clients.iter().map(|c| c.serve_instant).collect::<Vec<_>>();
+        assert_eq!(serves, [1., 1.5, 2., 2.5, 3., 3.5]);
+    }
+}
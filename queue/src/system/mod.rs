@@ -1,3 +1,6 @@
+use rand::rngs::Rng;
+use rand::series::TimeSeries;
+
 mod fifo;
 pub use fifo::Fifo;
 
@@ -11,3 +14,14 @@ pub struct Client<F> {
 pub trait QueueSystem<F> {
     fn step(&mut self) -> Client<F>;
 }
+
+// successive values of a series drawn from its own generator, e.g. as the
+// interarrival or serving times of a `Fifo`
+pub fn from_series<F, S, R>(mut series: S, mut rng: R) -> Box<dyn FnMut() -> F>
+where
+    F: 'static,
+    S: TimeSeries<F> + 'static,
+    R: Rng + 'static,
+{
+    Box::new(move || series.get_next(&mut rng))
+}
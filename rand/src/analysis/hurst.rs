@@ -0,0 +1,328 @@
+use num::complex::Complex;
+use num::fft::dft;
+use num::optimize::golden_section_max;
+use num::Float;
+
+use super::{regression, AnalysisError, HurstEstimate};
+
+// shortest series accepted by the estimators, so that the regressions have
+// enough scales to work with
+const MINIMUM_LEN: usize = 64;
+
+// log-spaced integers in [min, max], four per octave
+fn scales(min: usize, max: usize) -> Vec<usize> {
+    let mut scales = Vec::new();
+    let mut s = min as f64;
+    while s as usize <= max {
+        if scales.last() != Some(&(s as usize)) {
+            scales.push(s as usize);
+        }
+        s *= 2_f64.powf(0.25);
+    }
+    scales
+}
+
+fn mean<F>(data: &[F]) -> F
+where
+    F: Float,
+{
+    data.iter().fold(F::ZERO, |acc, &x| acc + x) / F::from(data.len() as f64)
+}
+
+fn check<F>(data: &[F]) -> Result<(), AnalysisError>
+where
+    F: Float,
+{
+    if data.len() < MINIMUM_LEN {
+        return Err(AnalysisError::NotEnoughData {
+            minimum: MINIMUM_LEN,
+        });
+    }
+    if data.iter().all(|&x| x == data[0]) {
+        return Err(AnalysisError::ConstantData);
+    }
+    Ok(())
+}
+
+// slope of (ln s, ln y(s)), skipping the scales where y is not positive
+fn log_log_slope<F>(points: impl Iterator<Item = (usize, F)>) -> (F, F)
+where
+    F: Float,
+{
+    let (x, y): (Vec<_>, Vec<_>) = points
+        .filter(|&(_, y)| y > F::ZERO)
+        .map(|(s, y)| (F::from(s as f64).ln(), y.ln()))
+        .unzip();
+    regression(&x, &y)
+}
+
+// R/S analysis: the range of the cumulative deviations over the standard
+// deviation, averaged over the blocks of size n, grows as n^H. Biased
+// towards 1/2 for strong dependence and short series.
+pub fn rescaled_range<F>(data: &[F]) -> Result<HurstEstimate<F>, AnalysisError>
+where
+    F: Float,
+{
+    check(data)?;
+
+    let points = scales(8, data.len() / 2).into_iter().map(|n| {
+        let (mut sum, mut count) = (F::ZERO, 0);
+        for block in data.chunks_exact(n) {
+            let m = mean(block);
+            let (mut y, mut min, mut max, mut ss) = (F::ZERO, F::ZERO, F::ZERO, F::ZERO);
+            for &x in block {
+                y += x - m;
+                min = min.min(y);
+                max = max.max(y);
+                ss += (x - m) * (x - m);
+            }
+            let s = (ss / F::from(n as f64)).sqrt();
+            if s > F::ZERO {
+                sum += (max - min) / s;
+                count += 1;
+            }
+        }
+        (n, sum / F::from(count as f64))
+    });
+
+    let (hurst, std_error) = log_log_slope(points);
+    Ok(HurstEstimate { hurst, std_error })
+}
+
+// aggregated variance: the variance of the means of blocks of size m
+// decays as m^(2H - 2). Biased downwards for strong dependence, the block
+// means being measured around the sample mean.
+pub fn aggregated_variance<F>(data: &[F]) -> Result<HurstEstimate<F>, AnalysisError>
+where
+    F: Float,
+{
+    check(data)?;
+
+    let points = scales(1, data.len() / 10).into_iter().map(|m| {
+        let means = data.chunks_exact(m).map(mean).collect::<Vec<_>>();
+        let grand_mean = mean(&means);
+        let ss = means
+            .iter()
+            .fold(F::ZERO, |acc, &x| acc + (x - grand_mean) * (x - grand_mean));
+        (m, ss / F::from((means.len() - 1) as f64))
+    });
+
+    let (slope, std_error) = log_log_slope(points);
+    Ok(HurstEstimate {
+        hurst: F::ONE + F::from(0.5) * slope,
+        std_error: F::from(0.5) * std_error,
+    })
+}
+
+// detrended fluctuation analysis (DFA-1): the root mean square residual of
+// the profile around a linear trend fitted in each box of size n grows as n^H
+pub fn dfa<F>(data: &[F]) -> Result<HurstEstimate<F>, AnalysisError>
+where
+    F: Float,
+{
+    check(data)?;
+
+    let m = mean(data);
+    let profile = data
+        .iter()
+        .scan(F::ZERO, |y, &x| {
+            *y += x - m;
+            Some(*y)
+        })
+        .collect::<Vec<_>>();
+
+    let points = scales(8, data.len() / 4).into_iter().map(|n| {
+        // the abscissae 0..n are the same in every box
+        let t_mean = F::from((n - 1) as f64) * F::from(0.5);
+        let stt = (0..n).fold(F::ZERO, |acc, t| {
+            let t = F::from(t as f64) - t_mean;
+            acc + t * t
+        });
+
+        let (mut rss, mut count) = (F::ZERO, 0);
+        for block in profile.chunks_exact(n) {
+            let y_mean = mean(block);
+            let mut sty = F::ZERO;
+            for (t, &y) in block.iter().enumerate() {
+                sty += (F::from(t as f64) - t_mean) * (y - y_mean);
+            }
+            let slope = sty / stt;
+            for (t, &y) in block.iter().enumerate() {
+                let r = y - y_mean - slope * (F::from(t as f64) - t_mean);
+                rss += r * r;
+            }
+            count += n;
+        }
+        (n, (rss / F::from(count as f64)).sqrt())
+    });
+
+    let (hurst, std_error) = log_log_slope(points);
+    Ok(HurstEstimate { hurst, std_error })
+}
+
+// shape of the spectral density of fractional Gaussian noise,
+// (1 - cos w) sum_k |2 pi k + w|^(-2H - 1), the terms |k| > K being
+// approximated by an integral
+fn fgn_spectral_shape<F>(hurst: F, w: F) -> F
+where
+    F: Float,
+{
+    const K: usize = 20;
+
+    let a = F::from(2.) * hurst + F::ONE;
+    let two_pi = F::from(2.) * F::PI;
+
+    let mut sum = w.powf(-a);
+    for k in 1..=K {
+        let k = two_pi * F::from(k as f64);
+        sum += (k + w).powf(-a) + (k - w).powf(-a);
+    }
+    let tail = two_pi * F::from(K as f64 + 0.5);
+    sum += ((tail + w).powf(F::ONE - a) + (tail - w).powf(F::ONE - a)) / (two_pi * (a - F::ONE));
+
+    (F::ONE - w.sin_cos().1) * sum
+}
+
+// Whittle estimator for fractional Gaussian noise: with I the periodogram
+// and g the shape of the spectral density, H minimizes
+// ln(mean I(w_j) / g(w_j)) + mean ln g(w_j) over the Fourier frequencies.
+// The asymptotic variance is 2 / (n Var(d/dH ln g(w_j))).
+pub fn whittle<F>(data: &[F]) -> Result<HurstEstimate<F>, AnalysisError>
+where
+    F: Float,
+{
+    check(data)?;
+
+    let n = data.len();
+    let m = mean(data);
+    let x = data
+        .iter()
+        .map(|&x| Complex::from_real(x - m))
+        .collect::<Vec<_>>();
+    let transform = dft(&x);
+
+    // the normalization of the periodogram does not change the estimate
+    let (periodogram, frequencies): (Vec<_>, Vec<_>) = (1..=(n - 1) / 2)
+        .map(|j| {
+            let w = F::from(2.) * F::PI * F::from(j as f64) / F::from(n as f64);
+            (transform[j].norm_sqr(), w)
+        })
+        .unzip();
+
+    let objective = |hurst: F| {
+        let (mut ratio, mut ln_g) = (F::ZERO, F::ZERO);
+        for (&i, &w) in periodogram.iter().zip(&frequencies) {
+            let g = fgn_spectral_shape(hurst, w);
+            ratio += i / g;
+            ln_g += g.ln();
+        }
+        let count = F::from(periodogram.len() as f64);
+        -((ratio / count).ln() + ln_g / count)
+    };
+    let hurst = golden_section_max(objective, F::from(0.01), F::from(0.99), F::from(1e-8));
+
+    // central differences for d/dH ln g
+    let h = F::from(1e-5);
+    let derivatives = frequencies
+        .iter()
+        .map(|&w| {
+            let up = fgn_spectral_shape(hurst + h, w).ln();
+            let down = fgn_spectral_shape(hurst - h, w).ln();
+            (up - down) / (F::from(2.) * h)
+        })
+        .collect::<Vec<_>>();
+    let d_mean = mean(&derivatives);
+    let variance = derivatives
+        .iter()
+        .fold(F::ZERO, |acc, &d| acc + (d - d_mean) * (d - d_mean))
+        / F::from(derivatives.len() as f64);
+
+    Ok(HurstEstimate {
+        hurst,
+        std_error: (F::from(2.) / (F::from(n as f64) * variance)).sqrt(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::StandardNormal;
+    use crate::rngs::{Rng, Xoshiro256PlusPlus};
+    use crate::series::FractionalGaussianNoise;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    type Estimator = fn(&[f64]) -> Result<HurstEstimate<f64>, AnalysisError>;
+
+    const ESTIMATORS: [(Estimator, f64); 4] = [
+        (rescaled_range, 0.1),
+        (aggregated_variance, 0.08),
+        (dfa, 0.06),
+        (whittle, 0.03),
+    ];
+
+    #[test]
+    fn white_noise() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let data = std::iter::repeat_with(|| rng.sample(&StandardNormal))
+            .take(1 << 14)
+            .collect::<Vec<f64>>();
+
+        for (estimator, tolerance) in ESTIMATORS {
+            let estimate = estimator(&data).unwrap();
+            assert!((estimate.hurst - 0.5).abs() < tolerance, "{estimate:?}");
+        }
+
+        let (low, high) = whittle(&data).unwrap().confidence_interval(0.95);
+        assert!(low < 0.5 && 0.5 < high);
+    }
+
+    #[test]
+    fn fractional_gaussian_noise() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let n = 10000;
+
+        for hurst in [0.3, 0.7] {
+            let mut fgn = FractionalGaussianNoise::new(hurst, 1., n);
+            let data = std::iter::repeat_with(|| rng.get_next(&mut fgn))
+                .take(n)
+                .collect::<Vec<f64>>();
+
+            for (estimator, tolerance) in ESTIMATORS {
+                let estimate = estimator(&data).unwrap();
+                assert!(
+                    (estimate.hurst - hurst).abs() < tolerance,
+                    "{hurst} {estimate:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn whittle_strong_dependence() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let n = 10000;
+
+        let mut fgn = FractionalGaussianNoise::new(0.9, 1., n);
+        let data = std::iter::repeat_with(|| rng.get_next(&mut fgn))
+            .take(n)
+            .collect::<Vec<f64>>();
+
+        let (low, high) = whittle(&data).unwrap().confidence_interval(0.99);
+        assert!(low < 0.9 && 0.9 < high);
+    }
+
+    #[test]
+    fn rejects_degenerate_data() {
+        assert_eq!(
+            dfa(&[1.; 10]),
+            Err(AnalysisError::NotEnoughData { minimum: 64 })
+        );
+        assert_eq!(whittle(&[1.; 100]), Err(AnalysisError::ConstantData));
+    }
+}
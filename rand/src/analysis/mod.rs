@@ -0,0 +1,76 @@
+use core::fmt;
+
+use num::special::normal_quantile;
+use num::Float;
+
+mod hurst;
+
+pub use hurst::{aggregated_variance, dfa, rescaled_range, whittle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisError {
+    NotEnoughData { minimum: usize },
+    ConstantData,
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughData { minimum } => {
+                write!(f, "at least {minimum} samples are needed")
+            }
+            Self::ConstantData => write!(f, "the series is constant"),
+        }
+    }
+}
+
+impl std::error::Error for AnalysisError {}
+
+// the standard error of the log-log estimators (R/S, aggregated variance,
+// DFA) is the one of the regression slope, which ignores the dependence
+// between scales and understates the uncertainty
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HurstEstimate<F> {
+    pub hurst: F,
+    pub std_error: F,
+}
+
+impl<F> HurstEstimate<F>
+where
+    F: Float,
+{
+    // asymptotic normal interval, e.g. level = 0.95
+    pub fn confidence_interval(&self, level: F) -> (F, F) {
+        let z = normal_quantile(F::from(0.5) + F::from(0.5) * level);
+        (
+            self.hurst - z * self.std_error,
+            self.hurst + z * self.std_error,
+        )
+    }
+}
+
+// least squares line through (x, y), the slope and its standard error
+fn regression<F>(x: &[F], y: &[F]) -> (F, F)
+where
+    F: Float,
+{
+    let n = F::from(x.len() as f64);
+    let mean_x = x.iter().fold(F::ZERO, |acc, &x| acc + x) / n;
+    let mean_y = y.iter().fold(F::ZERO, |acc, &y| acc + y) / n;
+
+    let (mut sxx, mut sxy) = (F::ZERO, F::ZERO);
+    for (&x, &y) in x.iter().zip(y) {
+        sxx += (x - mean_x) * (x - mean_x);
+        sxy += (x - mean_x) * (y - mean_y);
+    }
+    let slope = sxy / sxx;
+
+    let mut rss = F::ZERO;
+    for (&x, &y) in x.iter().zip(y) {
+        let r = y - mean_y - slope * (x - mean_x);
+        rss += r * r;
+    }
+    let std_error = (rss / (n - F::from(2.)) / sxx).sqrt();
+
+    (slope, std_error)
+}
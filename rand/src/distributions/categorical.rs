@@ -0,0 +1,119 @@
+use num::Float;
+
+use crate::rngs::Rng;
+
+use super::{Distribution, StandardUniformClosedOpen, UniformInt};
+
+// index i with probability proportional to weights[i], in constant time by
+// Walker's alias method: column i keeps i with probability cutoffs[i] and
+// gives aliases[i] otherwise
+#[derive(Clone)]
+pub struct Categorical<F> {
+    probabilities: Vec<F>,
+    cutoffs: Vec<F>,
+    aliases: Vec<usize>,
+    column: UniformInt<usize>,
+}
+
+impl<F> Categorical<F>
+where
+    F: Float,
+{
+    pub fn new(weights: &[F]) -> Self {
+        assert!(!weights.is_empty(), "there should be at least one weight");
+        assert!(
+            weights.iter().all(|&w| w >= F::ZERO),
+            "the weights should be non-negative"
+        );
+        let total = weights.iter().fold(F::ZERO, |acc, &w| acc + w);
+        assert!(total > F::ZERO, "the weights should not all be zero");
+
+        let n = weights.len();
+        let probabilities = weights.iter().map(|&w| w / total).collect::<Vec<_>>();
+
+        // Vose's construction: each column is filled by a small entry and
+        // topped up by a large one
+        let mut cutoffs = probabilities
+            .iter()
+            .map(|&p| p * F::from(n as f64))
+            .collect::<Vec<_>>();
+        let mut aliases = (0..n).collect::<Vec<_>>();
+        let (mut small, mut large): (Vec<_>, Vec<_>) = (0..n).partition(|&i| cutoffs[i] < F::ONE);
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            aliases[s] = l;
+            let excess = F::ONE - cutoffs[s];
+            cutoffs[l] -= excess;
+            if cutoffs[l] < F::ONE {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // what is left is 1 up to rounding
+        for i in small.into_iter().chain(large) {
+            cutoffs[i] = F::ONE;
+        }
+
+        Self {
+            probabilities,
+            cutoffs,
+            aliases,
+            column: UniformInt::<usize>::new(0, n),
+        }
+    }
+
+    pub fn probabilities(&self) -> &[F] {
+        &self.probabilities
+    }
+}
+
+impl<F> Distribution<usize> for Categorical<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+{
+    fn sample<R>(&self, rng: &mut R) -> usize
+    where
+        R: Rng + ?Sized,
+    {
+        let i = rng.sample(&self.column);
+        let u: F = rng.sample(&StandardUniformClosedOpen);
+        if u < self.cutoffs[i] {
+            i
+        } else {
+            self.aliases[i]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    #[test]
+    fn frequencies() {
+        let weights = [0.5, 0., 3., 1., 0.25, 0.25];
+        let categorical = Categorical::new(&weights);
+        assert_eq!(categorical.probabilities()[2], 0.6);
+
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let n = 500000;
+        let mut counts = [0; 6];
+        for _ in 0..n {
+            counts[rng.sample(&categorical)] += 1;
+        }
+
+        assert_eq!(counts[1], 0);
+        for (&count, &p) in counts.iter().zip(categorical.probabilities()) {
+            assert!((count as f64 / n as f64 - p).abs() < 0.003);
+        }
+    }
+}
@@ -2,8 +2,9 @@ use num::Float;
 
 use crate::rngs::Rng;
 
-use super::{Distribution, StandardUniformOpenClosed};
+use super::{Continuous, Distribution, Moments, Quantile, StandardUniformOpenClosed};
 
+#[derive(Clone)]
 pub struct Exponential<F> {
     mean: F,
 }
@@ -15,6 +16,10 @@ where
     pub fn new(mean: F) -> Self {
         Self { mean }
     }
+
+    pub fn mean(&self) -> F {
+        self.mean
+    }
 }
 
 impl<F> Distribution<F> for Exponential<F>
@@ -35,3 +40,44 @@ where
         -self.mean * u.ln()
     }
 }
+
+impl<F> Continuous<F> for Exponential<F>
+where
+    F: Float,
+{
+    fn ln_pdf(&self, x: F) -> F {
+        if x < F::ZERO {
+            return F::NEG_INFINITY;
+        }
+        -self.mean.ln() - x / self.mean
+    }
+
+    fn cdf(&self, x: F) -> F {
+        if x <= F::ZERO {
+            return F::ZERO;
+        }
+        F::ONE - (-x / self.mean).exp()
+    }
+}
+
+impl<F> Quantile<F> for Exponential<F>
+where
+    F: Float,
+{
+    fn quantile(&self, p: F) -> F {
+        -self.mean * (-p).ln_1p()
+    }
+}
+
+impl<F> Moments<F> for Exponential<F>
+where
+    F: Float,
+{
+    fn mean(&self) -> F {
+        self.mean
+    }
+
+    fn variance(&self) -> F {
+        self.mean * self.mean
+    }
+}
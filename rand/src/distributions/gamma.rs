@@ -0,0 +1,99 @@
+use num::special::{gamma_p, ln_gamma};
+use num::Float;
+
+use crate::rngs::Rng;
+
+use super::{Continuous, Distribution, Moments, StandardNormal, StandardUniformOpenClosed};
+
+#[derive(Clone)]
+pub struct Gamma<F> {
+    shape: F,
+    scale: F,
+}
+
+impl<F> Gamma<F>
+where
+    F: Float,
+{
+    pub fn new(shape: F, scale: F) -> Self {
+        Self { shape, scale }
+    }
+
+    pub fn shape(&self) -> F {
+        self.shape
+    }
+
+    pub fn scale(&self) -> F {
+        self.scale
+    }
+}
+
+impl<F> Distribution<F> for Gamma<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    // Marsaglia and Tsang's method
+    fn sample<R>(&self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        // X ~ Gamma(k + 1) => X U^(1/k) ~ Gamma(k)
+        let (shape, boost) = if self.shape < F::ONE {
+            let u: F = rng.sample(&StandardUniformOpenClosed);
+            (self.shape + F::ONE, u.powf(self.shape.recip()))
+        } else {
+            (self.shape, F::ONE)
+        };
+
+        let d = shape - F::from(1. / 3.);
+        let c = (F::from(9.) * d).sqrt().recip();
+
+        loop {
+            let x: F = rng.sample(&StandardNormal);
+            let v = F::ONE + c * x;
+            if v <= F::ZERO {
+                continue;
+            }
+            let v = v * v * v;
+
+            let u: F = rng.sample(&StandardUniformOpenClosed);
+            if u.ln() < F::from(0.5) * x * x + d - d * v + d * v.ln() {
+                return d * v * boost * self.scale;
+            }
+        }
+    }
+}
+
+impl<F> Continuous<F> for Gamma<F>
+where
+    F: Float,
+{
+    fn ln_pdf(&self, x: F) -> F {
+        if x < F::ZERO {
+            return F::NEG_INFINITY;
+        }
+        (self.shape - F::ONE) * x.ln()
+            - x / self.scale
+            - ln_gamma(self.shape)
+            - self.shape * self.scale.ln()
+    }
+
+    fn cdf(&self, x: F) -> F {
+        gamma_p(self.shape, x / self.scale)
+    }
+}
+
+impl<F> Moments<F> for Gamma<F>
+where
+    F: Float,
+{
+    fn mean(&self) -> F {
+        self.shape * self.scale
+    }
+
+    fn variance(&self) -> F {
+        self.shape * self.scale * self.scale
+    }
+}
@@ -0,0 +1,55 @@
+use crate::rngs::Rng;
+
+use super::Distribution;
+
+// uniform integer in [start, end)
+#[derive(Clone)]
+pub struct UniformInt<T> {
+    start: T,
+    range: T,
+}
+
+macro_rules! uniform_int_impl {
+    ($ity:ty, $uty:ty, $wide:ty, $gen:ident) => {
+        impl UniformInt<$ity> {
+            pub fn new(start: $ity, end: $ity) -> Self {
+                assert!(start < end, "the range should not be empty");
+                Self {
+                    start,
+                    range: end - start,
+                }
+            }
+        }
+
+        impl From<core::ops::Range<$ity>> for UniformInt<$ity> {
+            fn from(range: core::ops::Range<$ity>) -> Self {
+                Self::new(range.start, range.end)
+            }
+        }
+
+        impl Distribution<$ity> for UniformInt<$ity> {
+            // Lemire's nearly divisionless method, unbiased
+            fn sample<R>(&self, rng: &mut R) -> $ity
+            where
+                R: Rng + ?Sized,
+            {
+                let range = self.range as $uty;
+                let bits = <$uty>::BITS;
+
+                let mut m = rng.$gen() as $uty as $wide * range as $wide;
+                if (m as $uty) < range {
+                    let threshold = range.wrapping_neg() % range;
+                    while (m as $uty) < threshold {
+                        m = rng.$gen() as $uty as $wide * range as $wide;
+                    }
+                }
+
+                self.start + (m >> bits) as $ity
+            }
+        }
+    };
+}
+
+uniform_int_impl! { u32, u32, u64, next_u32 }
+uniform_int_impl! { u64, u64, u128, next_u64 }
+uniform_int_impl! { usize, u64, u128, next_u64 }
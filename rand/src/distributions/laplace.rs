@@ -0,0 +1,89 @@
+use num::Float;
+
+use crate::rngs::Rng;
+
+use super::{Continuous, Distribution, Moments, Quantile, StandardUniformOpenOpen};
+
+#[derive(Clone)]
+pub struct Laplace<F> {
+    location: F,
+    scale: F,
+}
+
+impl<F> Laplace<F>
+where
+    F: Float,
+{
+    pub fn new(location: F, scale: F) -> Self {
+        Self { location, scale }
+    }
+
+    pub fn location(&self) -> F {
+        self.location
+    }
+
+    pub fn scale(&self) -> F {
+        self.scale
+    }
+}
+
+impl<F> Distribution<F> for Laplace<F>
+where
+    F: Float,
+    StandardUniformOpenOpen: Distribution<F>,
+{
+    // inverse CDF sampling technique
+    fn sample<R>(&self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let u = rng.sample(&StandardUniformOpenOpen);
+        self.quantile(u)
+    }
+}
+
+impl<F> Continuous<F> for Laplace<F>
+where
+    F: Float,
+{
+    fn ln_pdf(&self, x: F) -> F {
+        -(F::from(2.) * self.scale).ln() - (x - self.location).abs() / self.scale
+    }
+
+    fn cdf(&self, x: F) -> F {
+        let half = F::from(0.5);
+        let z = (x - self.location) / self.scale;
+        if z < F::ZERO {
+            half * z.exp()
+        } else {
+            F::ONE - half * (-z).exp()
+        }
+    }
+}
+
+impl<F> Quantile<F> for Laplace<F>
+where
+    F: Float,
+{
+    fn quantile(&self, p: F) -> F {
+        let half = F::from(0.5);
+        if p < half {
+            self.location + self.scale * (F::from(2.) * p).ln()
+        } else {
+            self.location - self.scale * (F::from(2.) * (F::ONE - p)).ln()
+        }
+    }
+}
+
+impl<F> Moments<F> for Laplace<F>
+where
+    F: Float,
+{
+    fn mean(&self) -> F {
+        self.location
+    }
+
+    fn variance(&self) -> F {
+        F::from(2.) * self.scale * self.scale
+    }
+}
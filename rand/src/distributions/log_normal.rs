@@ -0,0 +1,89 @@
+use num::special::{normal_cdf, normal_quantile};
+use num::Float;
+
+use crate::rngs::Rng;
+
+use super::{Continuous, Distribution, Moments, Quantile, StandardNormal};
+
+#[derive(Clone)]
+pub struct LogNormal<F> {
+    mu: F,
+    sigma: F,
+}
+
+impl<F> LogNormal<F>
+where
+    F: Float,
+{
+    // mu and sigma are the mean and standard deviation of ln(x)
+    pub fn new(mu: F, sigma: F) -> Self {
+        Self { mu, sigma }
+    }
+
+    pub fn mu(&self) -> F {
+        self.mu
+    }
+
+    pub fn sigma(&self) -> F {
+        self.sigma
+    }
+}
+
+impl<F> Distribution<F> for LogNormal<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+{
+    fn sample<R>(&self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let z = rng.sample(&StandardNormal);
+        z.mul_add(self.sigma, self.mu).exp()
+    }
+}
+
+impl<F> Continuous<F> for LogNormal<F>
+where
+    F: Float,
+{
+    fn ln_pdf(&self, x: F) -> F {
+        if x <= F::ZERO {
+            return F::NEG_INFINITY;
+        }
+        let ln_x = x.ln();
+        let z = (ln_x - self.mu) / self.sigma;
+        -ln_x - self.sigma.ln() - F::from(0.5) * (F::from(2.) * F::PI).ln() - F::from(0.5) * z * z
+    }
+
+    fn cdf(&self, x: F) -> F {
+        if x <= F::ZERO {
+            return F::ZERO;
+        }
+        normal_cdf((x.ln() - self.mu) / self.sigma)
+    }
+}
+
+impl<F> Quantile<F> for LogNormal<F>
+where
+    F: Float,
+{
+    fn quantile(&self, p: F) -> F {
+        normal_quantile(p).mul_add(self.sigma, self.mu).exp()
+    }
+}
+
+impl<F> Moments<F> for LogNormal<F>
+where
+    F: Float,
+{
+    fn mean(&self) -> F {
+        (self.mu + F::from(0.5) * self.sigma * self.sigma).exp()
+    }
+
+    // (e^(sigma^2) - 1) e^(2 mu + sigma^2)
+    fn variance(&self) -> F {
+        let mean = self.mean();
+        ((self.sigma * self.sigma).exp() - F::ONE) * mean * mean
+    }
+}
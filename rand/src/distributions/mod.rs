@@ -2,19 +2,55 @@ use crate::rngs::Rng;
 
 mod normal;
 mod uniform;
+mod integer;
 mod exponential;
 mod pareto;
+mod gamma;
+mod log_normal;
+mod weibull;
+mod phase_type;
+mod multivariate_normal;
+mod student_t;
+mod laplace;
+mod shifted;
+mod categorical;
 
 pub use normal::{Normal, StandardNormal};
 pub use exponential::Exponential;
+pub use integer::UniformInt;
 pub use uniform::{
     StandardUniformClosedOpen, StandardUniformOpenClosed, StandardUniformOpenOpen,
     UniformClosedOpen, UniformOpenClosed, UniformOpenOpen,
 };
 pub use pareto::ParetoII;
+pub use gamma::Gamma;
+pub use log_normal::LogNormal;
+pub use weibull::Weibull;
+pub use phase_type::{Hyperexponential, MixedErlang, PhaseType};
+pub use multivariate_normal::{GaussianCopula, MultivariateNormal};
+pub use student_t::StudentT;
+pub use laplace::Laplace;
+pub use shifted::Shifted;
+pub use categorical::Categorical;
 
 pub trait Distribution<T> {
     fn sample<R>(&self, rng: &mut R) -> T
     where
         R: Rng + ?Sized;
 }
+
+pub trait Continuous<F> {
+    fn ln_pdf(&self, x: F) -> F;
+    fn cdf(&self, x: F) -> F;
+}
+
+// inverse of the CDF, maps a uniform in (0, 1) to a sample
+pub trait Quantile<F> {
+    fn quantile(&self, p: F) -> F;
+}
+
+// infinite when the integral diverges, e.g. the variance of heavy tails
+pub trait Moments<F> {
+    fn mean(&self) -> F;
+    fn variance(&self) -> F;
+}
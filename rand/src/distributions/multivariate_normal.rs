@@ -0,0 +1,237 @@
+use num::linalg::{LinalgError, Matrix};
+use num::special::normal_cdf;
+use num::Float;
+
+use crate::rngs::Rng;
+
+use super::{Distribution, StandardNormal};
+
+#[derive(Clone)]
+pub struct MultivariateNormal<F> {
+    mean: Vec<F>,
+    // lower triangular Cholesky factor of the covariance matrix
+    factor: Matrix<F>,
+}
+
+impl<F> MultivariateNormal<F>
+where
+    F: Float,
+{
+    // fails unless the covariance matrix is symmetric positive definite and
+    // matches the dimension of the mean
+    pub fn new(mean: Vec<F>, covariance: &Matrix<F>) -> Result<Self, LinalgError> {
+        if covariance.rows() != mean.len() {
+            return Err(LinalgError::DimensionMismatch);
+        }
+        if !covariance.is_symmetric() {
+            return Err(LinalgError::NotSymmetric);
+        }
+        let factor = covariance.cholesky()?;
+        Ok(Self { mean, factor })
+    }
+
+    // degenerate distributions are allowed, the samples then lie on a
+    // lower dimensional subspace
+    pub fn new_semidefinite(mean: Vec<F>, covariance: &Matrix<F>) -> Result<Self, LinalgError> {
+        if covariance.rows() != mean.len() {
+            return Err(LinalgError::DimensionMismatch);
+        }
+        if !covariance.is_symmetric() {
+            return Err(LinalgError::NotSymmetric);
+        }
+        let factor = covariance.cholesky_semidefinite()?;
+        Ok(Self { mean, factor })
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.mean.len()
+    }
+
+    pub fn mean(&self) -> &[F] {
+        &self.mean
+    }
+
+    pub fn sample_into<R>(&self, rng: &mut R, x: &mut [F])
+    where
+        R: Rng + ?Sized,
+        StandardNormal: Distribution<F>,
+    {
+        assert_eq!(x.len(), self.dimension());
+
+        // x = mean + L z, z is filled in place since L is lower triangular
+        for z in x.iter_mut() {
+            *z = rng.sample(&StandardNormal);
+        }
+        for i in (0..x.len()).rev() {
+            let mut sum = self.mean[i];
+            for (j, &z) in x[..=i].iter().enumerate() {
+                sum += self.factor[(i, j)] * z;
+            }
+            x[i] = sum;
+        }
+    }
+}
+
+impl<F> Distribution<Vec<F>> for MultivariateNormal<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+{
+    fn sample<R>(&self, rng: &mut R) -> Vec<F>
+    where
+        R: Rng + ?Sized,
+    {
+        let mut x = vec![F::ZERO; self.dimension()];
+        self.sample_into(rng, &mut x);
+        x
+    }
+}
+
+// uniform marginals coupled by the dependence structure of a multivariate
+// normal with the given correlation matrix. apply the `Quantile` of each
+// marginal distribution to the coordinates to get correlated inputs, e.g.
+// correlated arrival and service times
+#[derive(Clone)]
+pub struct GaussianCopula<F> {
+    normal: MultivariateNormal<F>,
+}
+
+impl<F> GaussianCopula<F>
+where
+    F: Float,
+{
+    pub fn new(correlation: &Matrix<F>) -> Result<Self, LinalgError> {
+        let n = correlation.rows();
+        if !correlation.is_square() {
+            return Err(LinalgError::DimensionMismatch);
+        }
+        let tol = F::from(n as f64) * F::EPSILON;
+        if (0..n).any(|i| (correlation[(i, i)] - F::ONE).abs() > tol) {
+            return Err(LinalgError::NotCorrelation);
+        }
+        let mean = vec![F::ZERO; n];
+        Ok(Self {
+            normal: MultivariateNormal::new(mean, correlation)?,
+        })
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.normal.dimension()
+    }
+
+    pub fn sample_into<R>(&self, rng: &mut R, u: &mut [F])
+    where
+        R: Rng + ?Sized,
+        StandardNormal: Distribution<F>,
+    {
+        self.normal.sample_into(rng, u);
+        for u in u {
+            *u = normal_cdf(*u);
+        }
+    }
+}
+
+impl<F> Distribution<Vec<F>> for GaussianCopula<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+{
+    fn sample<R>(&self, rng: &mut R) -> Vec<F>
+    where
+        R: Rng + ?Sized,
+    {
+        let mut u = vec![F::ZERO; self.dimension()];
+        self.sample_into(rng, &mut u);
+        u
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::{Exponential, Quantile};
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    const COUNT: usize = 50000;
+
+    #[test]
+    fn multivariate_normal_moments() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let covariance = Matrix::from_rows(&[[4., 1.2, 0.], [1.2, 1., -0.5], [0., -0.5, 2.]]);
+        let mvn = MultivariateNormal::new(vec![1., -2., 0.], &covariance).unwrap();
+
+        let samples = std::iter::repeat_with(|| rng.sample(&mvn))
+            .take(COUNT)
+            .collect::<Vec<_>>();
+
+        for i in 0..3 {
+            let mean_i = samples.iter().map(|x| x[i]).sum::<f64>() / COUNT as f64;
+            assert!((mean_i - mvn.mean()[i]).abs() < 0.05);
+
+            for j in 0..3 {
+                let mean_j = samples.iter().map(|x| x[j]).sum::<f64>() / COUNT as f64;
+                let cov = samples
+                    .iter()
+                    .map(|x| (x[i] - mean_i) * (x[j] - mean_j))
+                    .sum::<f64>()
+                    / COUNT as f64;
+                assert!((cov - covariance[(i, j)]).abs() < 0.1);
+            }
+        }
+    }
+
+    #[test]
+    fn gaussian_copula_marginals() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let correlation = Matrix::from_rows(&[[1., 0.8], [0.8, 1.]]);
+        let copula = GaussianCopula::new(&correlation).unwrap();
+        let exponential = Exponential::new(2.);
+
+        let mut sum = [0.; 2];
+        let mut product = 0.;
+        for _ in 0..COUNT {
+            let u = rng.sample(&copula);
+            let x = [exponential.quantile(u[0]), exponential.quantile(u[1])];
+            sum[0] += x[0];
+            sum[1] += x[1];
+            product += x[0] * x[1];
+        }
+
+        let mean = sum.map(|s| s / COUNT as f64);
+        let correlation = (product / COUNT as f64 - mean[0] * mean[1]) / 4.;
+        assert!((mean[0] - 2.).abs() < 0.05 && (mean[1] - 2.).abs() < 0.05);
+        assert!(correlation > 0.6 && correlation < 0.9);
+    }
+
+    #[test]
+    fn rejects_bad_covariance() {
+        let covariance = Matrix::from_rows(&[[1., 2.], [2., 1.]]);
+        assert!(MultivariateNormal::new(vec![0., 0.], &covariance).is_err());
+        assert!(MultivariateNormal::new(vec![0.], &Matrix::identity(2)).is_err());
+
+        // cholesky only reads the lower triangle
+        let covariance = Matrix::from_rows(&[[2., 1.], [0., 2.]]);
+        assert_eq!(
+            MultivariateNormal::new(vec![0., 0.], &covariance).err(),
+            Some(LinalgError::NotSymmetric)
+        );
+        assert_eq!(
+            MultivariateNormal::new_semidefinite(vec![0., 0.], &covariance).err(),
+            Some(LinalgError::NotSymmetric)
+        );
+
+        let covariance = Matrix::from_rows(&[[2., 0.5], [0.5, 1.]]);
+        assert_eq!(
+            GaussianCopula::new(&covariance).err(),
+            Some(LinalgError::NotCorrelation)
+        );
+        assert!(GaussianCopula::new(&Matrix::from_rows(&[[1., 0.5], [0.5, 1.]])).is_ok());
+    }
+}
@@ -1,9 +1,14 @@
+use num::special::{normal_cdf, normal_quantile};
 use num::Float;
 
 use crate::rngs::Rng;
 
-use super::{Distribution, StandardUniformClosedOpen, StandardUniformOpenClosed};
+use super::{
+    Continuous, Distribution, Moments, Quantile, StandardUniformClosedOpen,
+    StandardUniformOpenClosed,
+};
 
+#[derive(Clone)]
 pub struct StandardNormal;
 
 impl<F> Distribution<F> for StandardNormal
@@ -30,6 +35,7 @@ where
     }
 }
 
+#[derive(Clone)]
 pub struct Normal<F> {
     mean: F,
     std_dev: F,
@@ -42,6 +48,14 @@ where
     pub fn new(mean: F, std_dev: F) -> Self {
         Self { mean, std_dev }
     }
+
+    pub fn mean(&self) -> F {
+        self.mean
+    }
+
+    pub fn std_dev(&self) -> F {
+        self.std_dev
+    }
 }
 
 impl<F> Distribution<F> for Normal<F>
@@ -57,3 +71,39 @@ where
         z.mul_add(self.std_dev, self.mean)
     }
 }
+
+impl<F> Continuous<F> for Normal<F>
+where
+    F: Float,
+{
+    fn ln_pdf(&self, x: F) -> F {
+        let z = (x - self.mean) / self.std_dev;
+        -F::from(0.5) * (F::from(2.) * F::PI).ln() - self.std_dev.ln() - F::from(0.5) * z * z
+    }
+
+    fn cdf(&self, x: F) -> F {
+        normal_cdf((x - self.mean) / self.std_dev)
+    }
+}
+
+impl<F> Quantile<F> for Normal<F>
+where
+    F: Float,
+{
+    fn quantile(&self, p: F) -> F {
+        normal_quantile(p).mul_add(self.std_dev, self.mean)
+    }
+}
+
+impl<F> Moments<F> for Normal<F>
+where
+    F: Float,
+{
+    fn mean(&self) -> F {
+        self.mean
+    }
+
+    fn variance(&self) -> F {
+        self.std_dev * self.std_dev
+    }
+}
@@ -2,8 +2,9 @@ use num::Float;
 
 use crate::rngs::Rng;
 
-use super::{Distribution, StandardUniformClosedOpen};
+use super::{Continuous, Distribution, Moments, Quantile, StandardUniformClosedOpen};
 
+#[derive(Clone)]
 pub struct ParetoII<F> {
     pow: F,
     scale: F,
@@ -19,6 +20,14 @@ where
             scale,
         }
     }
+
+    pub fn shape(&self) -> F {
+        -self.pow.recip()
+    }
+
+    pub fn scale(&self) -> F {
+        self.scale
+    }
 }
 
 impl<F> Distribution<F> for ParetoII<F>
@@ -40,3 +49,57 @@ where
         self.scale * (u.powf(self.pow) - F::ONE)
     }
 }
+
+impl<F> Continuous<F> for ParetoII<F>
+where
+    F: Float,
+{
+    // f(x) = alpha / xm * (1 + x / xm) ^ -(alpha + 1)
+    fn ln_pdf(&self, x: F) -> F {
+        if x < F::ZERO {
+            return F::NEG_INFINITY;
+        }
+        let shape = self.shape();
+        shape.ln() - self.scale.ln() - (shape + F::ONE) * (x / self.scale).ln_1p()
+    }
+
+    fn cdf(&self, x: F) -> F {
+        if x <= F::ZERO {
+            return F::ZERO;
+        }
+        F::ONE - (F::ONE + x / self.scale).powf(self.pow.recip())
+    }
+}
+
+impl<F> Quantile<F> for ParetoII<F>
+where
+    F: Float,
+{
+    fn quantile(&self, p: F) -> F {
+        self.scale * ((F::ONE - p).powf(self.pow) - F::ONE)
+    }
+}
+
+impl<F> Moments<F> for ParetoII<F>
+where
+    F: Float,
+{
+    // infinite for shape <= 1
+    fn mean(&self) -> F {
+        let shape = self.shape();
+        if shape <= F::ONE {
+            return F::INFINITY;
+        }
+        self.scale / (shape - F::ONE)
+    }
+
+    // infinite for shape <= 2
+    fn variance(&self) -> F {
+        let shape = self.shape();
+        if shape <= F::from(2.) {
+            return F::INFINITY;
+        }
+        let mean = self.mean();
+        mean * mean * shape / (shape - F::from(2.))
+    }
+}
@@ -0,0 +1,221 @@
+use num::special::{gamma_p, ln_gamma};
+use num::Float;
+
+use crate::rngs::Rng;
+
+use super::{Continuous, Distribution, StandardUniformClosedOpen, StandardUniformOpenClosed};
+
+// mixture of exponentials, phase i is picked with probability p_i
+#[derive(Clone)]
+pub struct Hyperexponential<F> {
+    probabilities: Vec<F>,
+    means: Vec<F>,
+}
+
+impl<F> Hyperexponential<F>
+where
+    F: Float,
+{
+    pub fn new(probabilities: Vec<F>, means: Vec<F>) -> Self {
+        assert_eq!(
+            probabilities.len(),
+            means.len(),
+            "every phase should have a probability and a mean",
+        );
+        Self {
+            probabilities,
+            means,
+        }
+    }
+
+    pub fn probabilities(&self) -> &[F] {
+        &self.probabilities
+    }
+
+    pub fn means(&self) -> &[F] {
+        &self.means
+    }
+}
+
+impl<F> Distribution<F> for Hyperexponential<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn sample<R>(&self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let mut u = rng.sample(&StandardUniformClosedOpen);
+        let mut phase = self.means.len() - 1;
+        for (i, &p) in self.probabilities.iter().enumerate() {
+            if u < p {
+                phase = i;
+                break;
+            }
+            u -= p;
+        }
+
+        let v: F = rng.sample(&StandardUniformOpenClosed);
+        -self.means[phase] * v.ln()
+    }
+}
+
+impl<F> Continuous<F> for Hyperexponential<F>
+where
+    F: Float,
+{
+    fn ln_pdf(&self, x: F) -> F {
+        if x < F::ZERO {
+            return F::NEG_INFINITY;
+        }
+        let mut pdf = F::ZERO;
+        for (&p, &mean) in self.probabilities.iter().zip(&self.means) {
+            pdf += p / mean * (-x / mean).exp();
+        }
+        pdf.ln()
+    }
+
+    fn cdf(&self, x: F) -> F {
+        if x <= F::ZERO {
+            return F::ZERO;
+        }
+        let mut survival = F::ZERO;
+        for (&p, &mean) in self.probabilities.iter().zip(&self.means) {
+            survival += p * (-x / mean).exp();
+        }
+        F::ONE - survival
+    }
+}
+
+// Erlang(k - 1, rate) with probability p, Erlang(k, rate) otherwise
+#[derive(Clone)]
+pub struct MixedErlang<F> {
+    p: F,
+    k: usize,
+    rate: F,
+}
+
+impl<F> MixedErlang<F>
+where
+    F: Float,
+{
+    pub fn new(p: F, k: usize, rate: F) -> Self {
+        assert!(k >= 1, "the number of phases should be at least 1");
+        Self { p, k, rate }
+    }
+
+    pub fn p(&self) -> F {
+        self.p
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn rate(&self) -> F {
+        self.rate
+    }
+
+    fn erlang_ln_pdf(&self, n: usize, x: F) -> F {
+        if n == 0 {
+            // point mass at zero
+            return F::NEG_INFINITY;
+        }
+        let n = F::from(n as f64);
+        n * self.rate.ln() + (n - F::ONE) * x.ln() - self.rate * x - ln_gamma(n)
+    }
+
+    fn erlang_cdf(&self, n: usize, x: F) -> F {
+        if n == 0 {
+            return F::ONE;
+        }
+        gamma_p(F::from(n as f64), self.rate * x)
+    }
+}
+
+impl<F> Distribution<F> for MixedErlang<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn sample<R>(&self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let u: F = rng.sample(&StandardUniformClosedOpen);
+        let n = if u < self.p { self.k - 1 } else { self.k };
+
+        let mut sum = F::ZERO;
+        for _ in 0..n {
+            let v: F = rng.sample(&StandardUniformOpenClosed);
+            sum -= v.ln();
+        }
+        sum / self.rate
+    }
+}
+
+impl<F> Continuous<F> for MixedErlang<F>
+where
+    F: Float,
+{
+    fn ln_pdf(&self, x: F) -> F {
+        if x <= F::ZERO {
+            return F::NEG_INFINITY;
+        }
+        let pdf = self.p * self.erlang_ln_pdf(self.k - 1, x).exp()
+            + (F::ONE - self.p) * self.erlang_ln_pdf(self.k, x).exp();
+        pdf.ln()
+    }
+
+    fn cdf(&self, x: F) -> F {
+        if x < F::ZERO {
+            return F::ZERO;
+        }
+        self.p * self.erlang_cdf(self.k - 1, x) + (F::ONE - self.p) * self.erlang_cdf(self.k, x)
+    }
+}
+
+// the two-moment phase-type distributions
+pub enum PhaseType<F> {
+    Hyperexponential(Hyperexponential<F>),
+    MixedErlang(MixedErlang<F>),
+}
+
+impl<F> Distribution<F> for PhaseType<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn sample<R>(&self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        match self {
+            Self::Hyperexponential(d) => d.sample(rng),
+            Self::MixedErlang(d) => d.sample(rng),
+        }
+    }
+}
+
+impl<F> Continuous<F> for PhaseType<F>
+where
+    F: Float,
+{
+    fn ln_pdf(&self, x: F) -> F {
+        match self {
+            Self::Hyperexponential(d) => d.ln_pdf(x),
+            Self::MixedErlang(d) => d.ln_pdf(x),
+        }
+    }
+
+    fn cdf(&self, x: F) -> F {
+        match self {
+            Self::Hyperexponential(d) => d.cdf(x),
+            Self::MixedErlang(d) => d.cdf(x),
+        }
+    }
+}
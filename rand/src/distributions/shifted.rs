@@ -0,0 +1,84 @@
+use num::Float;
+
+use crate::rngs::Rng;
+
+use super::{Continuous, Distribution, Moments, Quantile};
+
+// X + shift, e.g. a centered exponential for zero mean but skewed
+// innovations, `Shifted::new(Exponential::new(mean), -mean)`
+#[derive(Clone)]
+pub struct Shifted<D, F> {
+    distribution: D,
+    shift: F,
+}
+
+impl<D, F> Shifted<D, F>
+where
+    F: Float,
+{
+    pub fn new(distribution: D, shift: F) -> Self {
+        Self {
+            distribution,
+            shift,
+        }
+    }
+
+    pub fn distribution(&self) -> &D {
+        &self.distribution
+    }
+
+    pub fn shift(&self) -> F {
+        self.shift
+    }
+}
+
+impl<D, F> Distribution<F> for Shifted<D, F>
+where
+    D: Distribution<F>,
+    F: Float,
+{
+    fn sample<R>(&self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        rng.sample(&self.distribution) + self.shift
+    }
+}
+
+impl<D, F> Continuous<F> for Shifted<D, F>
+where
+    D: Continuous<F>,
+    F: Float,
+{
+    fn ln_pdf(&self, x: F) -> F {
+        self.distribution.ln_pdf(x - self.shift)
+    }
+
+    fn cdf(&self, x: F) -> F {
+        self.distribution.cdf(x - self.shift)
+    }
+}
+
+impl<D, F> Quantile<F> for Shifted<D, F>
+where
+    D: Quantile<F>,
+    F: Float,
+{
+    fn quantile(&self, p: F) -> F {
+        self.distribution.quantile(p) + self.shift
+    }
+}
+
+impl<D, F> Moments<F> for Shifted<D, F>
+where
+    D: Moments<F>,
+    F: Float,
+{
+    fn mean(&self) -> F {
+        self.distribution.mean() + self.shift
+    }
+
+    fn variance(&self) -> F {
+        self.distribution.variance()
+    }
+}
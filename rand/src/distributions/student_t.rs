@@ -0,0 +1,81 @@
+use num::special::ln_gamma;
+use num::Float;
+
+use crate::rngs::Rng;
+
+use super::{Distribution, Gamma, Moments, StandardNormal, StandardUniformOpenClosed};
+
+// centered at zero, heavier tails than the normal for small degrees of freedom
+#[derive(Clone)]
+pub struct StudentT<F> {
+    dof: F,
+    scale: F,
+    chi_squared: Gamma<F>,
+}
+
+impl<F> StudentT<F>
+where
+    F: Float,
+{
+    pub fn new(dof: F, scale: F) -> Self {
+        Self {
+            dof,
+            scale,
+            chi_squared: Gamma::new(dof / F::from(2.), F::from(2.)),
+        }
+    }
+
+    pub fn dof(&self) -> F {
+        self.dof
+    }
+
+    pub fn scale(&self) -> F {
+        self.scale
+    }
+
+    pub fn ln_pdf(&self, x: F) -> F {
+        let half = F::from(0.5);
+        let t = x / self.scale;
+        ln_gamma(half * (self.dof + F::ONE))
+            - ln_gamma(half * self.dof)
+            - half * (self.dof * F::PI).ln()
+            - self.scale.ln()
+            - half * (self.dof + F::ONE) * (t * t / self.dof).ln_1p()
+    }
+}
+
+impl<F> Distribution<F> for StudentT<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    // Z / sqrt(V / dof), with Z ~ N(0, 1) and V ~ chi^2(dof)
+    fn sample<R>(&self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let z: F = rng.sample(&StandardNormal);
+        let v = rng.sample(&self.chi_squared);
+
+        self.scale * z / (v / self.dof).sqrt()
+    }
+}
+
+impl<F> Moments<F> for StudentT<F>
+where
+    F: Float,
+{
+    // only defined for dof > 1, zero by symmetry
+    fn mean(&self) -> F {
+        F::ZERO
+    }
+
+    // infinite for dof <= 2
+    fn variance(&self) -> F {
+        if self.dof <= F::from(2.) {
+            return F::INFINITY;
+        }
+        self.scale * self.scale * self.dof / (self.dof - F::from(2.))
+    }
+}
@@ -4,10 +4,13 @@ use crate::rngs::Rng;
 
 use super::Distribution;
 
+#[derive(Clone)]
 pub struct StandardUniformClosedOpen;
 
+#[derive(Clone)]
 pub struct StandardUniformOpenClosed;
 
+#[derive(Clone)]
 pub struct StandardUniformOpenOpen;
 
 macro_rules! continuous_uniform_impl {
@@ -56,16 +59,19 @@ macro_rules! continuous_uniform_impl {
 continuous_uniform_impl! { f32, u32, next_u32, 32, 24 }
 continuous_uniform_impl! { f64, u64, next_u64, 64, 53 }
 
+#[derive(Clone)]
 pub struct UniformClosedOpen<F> {
     start: F,
     range: F,
 }
 
+#[derive(Clone)]
 pub struct UniformOpenClosed<F> {
     start: F,
     range: F,
 }
 
+#[derive(Clone)]
 pub struct UniformOpenOpen<F> {
     start: F,
     range: F,
@@ -0,0 +1,95 @@
+use num::special::ln_gamma;
+use num::Float;
+
+use crate::rngs::Rng;
+
+use super::{Continuous, Distribution, Moments, Quantile, StandardUniformOpenClosed};
+
+#[derive(Clone)]
+pub struct Weibull<F> {
+    shape: F,
+    scale: F,
+}
+
+impl<F> Weibull<F>
+where
+    F: Float,
+{
+    pub fn new(shape: F, scale: F) -> Self {
+        Self { shape, scale }
+    }
+
+    pub fn shape(&self) -> F {
+        self.shape
+    }
+
+    pub fn scale(&self) -> F {
+        self.scale
+    }
+}
+
+impl<F> Distribution<F> for Weibull<F>
+where
+    F: Float,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    // inverse CDF sampling technique
+    fn sample<R>(&self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let u = rng.sample(&StandardUniformOpenClosed);
+
+        // k = shape, lambda = scale
+        // u = 1 - F(x) = e^(-(x/lambda)^k)
+        // x = lambda (-ln(u))^(1/k)
+
+        self.scale * (-u.ln()).powf(self.shape.recip())
+    }
+}
+
+impl<F> Continuous<F> for Weibull<F>
+where
+    F: Float,
+{
+    fn ln_pdf(&self, x: F) -> F {
+        if x < F::ZERO {
+            return F::NEG_INFINITY;
+        }
+        let y = x / self.scale;
+        self.shape.ln() - self.scale.ln() + (self.shape - F::ONE) * y.ln() - y.powf(self.shape)
+    }
+
+    fn cdf(&self, x: F) -> F {
+        if x <= F::ZERO {
+            return F::ZERO;
+        }
+        F::ONE - (-(x / self.scale).powf(self.shape)).exp()
+    }
+}
+
+impl<F> Quantile<F> for Weibull<F>
+where
+    F: Float,
+{
+    fn quantile(&self, p: F) -> F {
+        self.scale * (-(-p).ln_1p()).powf(self.shape.recip())
+    }
+}
+
+impl<F> Moments<F> for Weibull<F>
+where
+    F: Float,
+{
+    // lambda Gamma(1 + 1/k)
+    fn mean(&self) -> F {
+        self.scale * ln_gamma(F::ONE + self.shape.recip()).exp()
+    }
+
+    // lambda^2 (Gamma(1 + 2/k) - Gamma(1 + 1/k)^2)
+    fn variance(&self) -> F {
+        let mean = self.mean();
+        let second = ln_gamma(F::ONE + F::from(2.) / self.shape).exp();
+        self.scale * self.scale * second - mean * mean
+    }
+}
@@ -0,0 +1,444 @@
+use core::fmt;
+
+use num::optimize::{bisect, golden_section_max};
+use num::special::digamma;
+use num::Float;
+
+use crate::distributions::{
+    Continuous, Exponential, Gamma, Hyperexponential, LogNormal, MixedErlang, Normal, ParetoII,
+    PhaseType, Weibull,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitError {
+    NotEnoughData { minimum: usize },
+    NonPositiveData,
+    NoConvergence,
+    InvalidEstimate,
+}
+
+impl fmt::Display for FitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughData { minimum } => {
+                write!(f, "at least {minimum} samples are needed")
+            }
+            Self::NonPositiveData => write!(f, "the distribution only supports positive samples"),
+            Self::NoConvergence => write!(f, "the estimator did not converge"),
+            Self::InvalidEstimate => {
+                write!(f, "the estimated parameters do not give a valid model")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FitError {}
+
+pub struct Fit<D, F> {
+    pub distribution: D,
+    pub log_likelihood: F,
+    // Kolmogorov-Smirnov statistic, sup |F_n(x) - F(x)|
+    pub ks_statistic: F,
+}
+
+fn check<F>(data: &[F], positive: bool) -> Result<(), FitError>
+where
+    F: Float,
+{
+    if data.len() < 2 {
+        return Err(FitError::NotEnoughData { minimum: 2 });
+    }
+    if positive && data.iter().any(|&x| x <= F::ZERO) {
+        return Err(FitError::NonPositiveData);
+    }
+    Ok(())
+}
+
+fn finish<D, F>(distribution: D, data: &[F]) -> Fit<D, F>
+where
+    D: Continuous<F>,
+    F: Float,
+{
+    let mut log_likelihood = F::ZERO;
+    for &x in data {
+        log_likelihood += distribution.ln_pdf(x);
+    }
+
+    let mut sorted = data.to_vec();
+    sorted.sort_by(F::total_cmp);
+
+    let n = F::from(sorted.len() as f64);
+    let mut ks_statistic = F::ZERO;
+    for (i, &x) in sorted.iter().enumerate() {
+        let cdf = distribution.cdf(x);
+        let below = F::from(i as f64) / n;
+        let above = F::from((i + 1) as f64) / n;
+        ks_statistic = ks_statistic.max(above - cdf).max(cdf - below);
+    }
+
+    Fit {
+        distribution,
+        log_likelihood,
+        ks_statistic,
+    }
+}
+
+fn mean<F>(data: &[F]) -> F
+where
+    F: Float,
+{
+    let mut sum = F::ZERO;
+    for &x in data {
+        sum += x;
+    }
+    sum / F::from(data.len() as f64)
+}
+
+// maximum likelihood variance, divides by n
+fn variance<F>(data: &[F], mean: F) -> F
+where
+    F: Float,
+{
+    let mut sum = F::ZERO;
+    for &x in data {
+        sum += (x - mean) * (x - mean);
+    }
+    sum / F::from(data.len() as f64)
+}
+
+fn mean_ln<F>(data: &[F]) -> F
+where
+    F: Float,
+{
+    let mut sum = F::ZERO;
+    for &x in data {
+        sum += x.ln();
+    }
+    sum / F::from(data.len() as f64)
+}
+
+// maximum likelihood, the sample mean
+pub fn exponential<F>(data: &[F]) -> Result<Fit<Exponential<F>, F>, FitError>
+where
+    F: Float,
+{
+    check(data, true)?;
+    Ok(finish(Exponential::new(mean(data)), data))
+}
+
+// maximum likelihood, the sample mean and the (biased) sample standard deviation
+pub fn normal<F>(data: &[F]) -> Result<Fit<Normal<F>, F>, FitError>
+where
+    F: Float,
+{
+    check(data, false)?;
+    let mean = mean(data);
+    let std_dev = variance(data, mean).sqrt();
+    Ok(finish(Normal::new(mean, std_dev), data))
+}
+
+// maximum likelihood, normal fit of ln(x)
+pub fn log_normal<F>(data: &[F]) -> Result<Fit<LogNormal<F>, F>, FitError>
+where
+    F: Float,
+{
+    check(data, true)?;
+    let ln_data = data.iter().map(|x| x.ln()).collect::<Vec<_>>();
+    let mu = mean(&ln_data);
+    let sigma = variance(&ln_data, mu).sqrt();
+    Ok(finish(LogNormal::new(mu, sigma), data))
+}
+
+// maximum likelihood, for a fixed scale lambda the shape estimate is
+// alpha(lambda) = n / sum(ln(1 + x / lambda)), the resulting profile
+// likelihood is maximized over ln(lambda)
+pub fn pareto_ii<F>(data: &[F]) -> Result<Fit<ParetoII<F>, F>, FitError>
+where
+    F: Float,
+{
+    check(data, true)?;
+
+    let n = F::from(data.len() as f64);
+    let shape_for = |scale: F| {
+        let mut sum = F::ZERO;
+        for &x in data {
+            sum += (x / scale).ln_1p();
+        }
+        (n / sum, sum)
+    };
+    let profile = |ln_scale: F| {
+        let scale = ln_scale.exp();
+        let (shape, sum) = shape_for(scale);
+        n * shape.ln() - n * scale.ln() - (shape + F::ONE) * sum
+    };
+
+    // the likelihood increases without bound towards the exponential
+    // distribution when the data has no heavy tail, and towards a vanishing
+    // scale when it spreads over many orders of magnitude
+    let center = mean(data).ln();
+    let width = F::from(20.);
+    let tol = F::from(1e-9).max(F::EPSILON);
+    let ln_scale = golden_section_max(profile, center - width, center + width, tol);
+    let margin = F::from(1e-3);
+    if ln_scale > center + width - margin || ln_scale < center - width + margin {
+        return Err(FitError::NoConvergence);
+    }
+
+    let scale = ln_scale.exp();
+    let (shape, _) = shape_for(scale);
+    Ok(finish(ParetoII::new(shape, scale), data))
+}
+
+// expands [lo, hi] around `start` by doubling until `f` changes sign,
+// `f` should be monotone
+fn bracket<F, G>(mut f: G, start: F) -> Result<(F, F), FitError>
+where
+    F: Float,
+    G: FnMut(F) -> F,
+{
+    let two = F::from(2.);
+    let positive = f(start) > F::ZERO;
+
+    let (mut lo, mut hi) = (start, start);
+    for _ in 0..100 {
+        lo /= two;
+        hi *= two;
+        if (f(lo) > F::ZERO) != positive {
+            return Ok((lo, lo * two));
+        }
+        if (f(hi) > F::ZERO) != positive {
+            return Ok((hi / two, hi));
+        }
+    }
+    Err(FitError::NoConvergence)
+}
+
+// maximum likelihood, the shape solves ln(k) - psi(k) = ln(mean(x)) - mean(ln(x))
+pub fn gamma<F>(data: &[F]) -> Result<Fit<Gamma<F>, F>, FitError>
+where
+    F: Float,
+{
+    check(data, true)?;
+
+    let mean = mean(data);
+    let s = mean.ln() - mean_ln(data);
+    if s <= F::ZERO {
+        return Err(FitError::NoConvergence);
+    }
+
+    // Minka's initial approximation
+    let three = F::from(3.);
+    let start =
+        (three - s + ((s - three) * (s - three) + F::from(24.) * s).sqrt()) / (F::from(12.) * s);
+
+    let f = |k: F| k.ln() - digamma(k) - s;
+    let (lo, hi) = bracket(f, start)?;
+    let shape = bisect(f, lo, hi, hi * F::EPSILON * F::from(4.));
+
+    Ok(finish(Gamma::new(shape, mean / shape), data))
+}
+
+// maximum likelihood, the shape solves
+// sum(x^k ln(x)) / sum(x^k) - 1/k - mean(ln(x)) = 0
+pub fn weibull<F>(data: &[F]) -> Result<Fit<Weibull<F>, F>, FitError>
+where
+    F: Float,
+{
+    check(data, true)?;
+
+    // x^k is computed as (x / max)^k max^k to avoid overflows
+    let max = data.iter().fold(F::ZERO, |m, &x| m.max(x));
+    let scaled = data.iter().map(|&x| x / max).collect::<Vec<_>>();
+    let mean_ln = mean_ln(&scaled);
+
+    let f = |k: F| {
+        let mut sum = F::ZERO;
+        let mut sum_ln = F::ZERO;
+        for &y in &scaled {
+            let yk = y.powf(k);
+            sum += yk;
+            sum_ln += yk * y.ln();
+        }
+        sum_ln / sum - k.recip() - mean_ln
+    };
+    let (lo, hi) = bracket(f, F::ONE)?;
+    let shape = bisect(f, lo, hi, hi * F::EPSILON * F::from(4.));
+
+    let mut sum = F::ZERO;
+    for &y in &scaled {
+        sum += y.powf(shape);
+    }
+    let scale = max * (sum / F::from(data.len() as f64)).powf(shape.recip());
+
+    Ok(finish(Weibull::new(shape, scale), data))
+}
+
+// matches the sample mean and squared coefficient of variation, with a
+// balanced-means two-phase hyperexponential when scv >= 1, and a mixture of
+// Erlang(k - 1) and Erlang(k) with a common rate when scv < 1 (Tijms)
+pub fn phase_type<F>(data: &[F]) -> Result<Fit<PhaseType<F>, F>, FitError>
+where
+    F: Float,
+{
+    check(data, true)?;
+
+    let mean = mean(data);
+    let n = F::from(data.len() as f64);
+    let sample_variance = variance(data, mean) * n / (n - F::ONE);
+    let scv = sample_variance / (mean * mean);
+
+    let two = F::from(2.);
+    let distribution = if scv >= F::ONE {
+        let p1 = (F::ONE + ((scv - F::ONE) / (scv + F::ONE)).sqrt()) / two;
+        let p2 = F::ONE - p1;
+        PhaseType::Hyperexponential(Hyperexponential::new(
+            vec![p1, p2],
+            vec![mean / (two * p1), mean / (two * p2)],
+        ))
+    } else {
+        if scv <= F::ZERO {
+            return Err(FitError::NoConvergence);
+        }
+
+        // 1/k <= scv < 1/(k - 1)
+        let mut k = 2;
+        while F::from(k as f64).recip() > scv {
+            k += 1;
+        }
+        let kf = F::from(k as f64);
+
+        let p = (kf * scv - (kf * (F::ONE + scv) - kf * kf * scv).sqrt()) / (F::ONE + scv);
+        let rate = (kf - p) / mean;
+        PhaseType::MixedErlang(MixedErlang::new(p, k, rate))
+    };
+
+    Ok(finish(distribution, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::Distribution;
+    use crate::rngs::{Rng, Xoshiro256PlusPlus};
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    const COUNT: usize = 20000;
+
+    fn samples<D: Distribution<f64>>(distribution: &D) -> Vec<f64> {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        std::iter::repeat_with(|| rng.sample(distribution))
+            .take(COUNT)
+            .collect()
+    }
+
+    // the 1% critical value of the KS statistic is about 1.63 / sqrt(n)
+    fn assert_fits<D, F>(fit: &Fit<D, f64>, data: &[f64], f: F)
+    where
+        F: Fn(&D) -> bool,
+    {
+        assert!(fit.ks_statistic < 1.63 / (data.len() as f64).sqrt());
+        assert!(fit.log_likelihood.is_finite());
+        assert!(f(&fit.distribution));
+    }
+
+    fn close(x: f64, y: f64, tol: f64) -> bool {
+        (x - y).abs() < tol * y.abs()
+    }
+
+    #[test]
+    fn fits_exponential() {
+        let data = samples(&Exponential::new(2.));
+        let fit = exponential(&data).unwrap();
+        assert_fits(&fit, &data, |d| close(d.mean(), 2., 0.03));
+    }
+
+    #[test]
+    fn fits_normal() {
+        let data = samples(&Normal::new(-1., 3.));
+        let fit = normal(&data).unwrap();
+        assert_fits(&fit, &data, |d| {
+            close(d.mean(), -1., 0.06) && close(d.std_dev(), 3., 0.03)
+        });
+    }
+
+    #[test]
+    fn fits_log_normal() {
+        let data = samples(&LogNormal::new(0.5, 0.8));
+        let fit = log_normal(&data).unwrap();
+        assert_fits(&fit, &data, |d| {
+            close(d.mu(), 0.5, 0.03) && close(d.sigma(), 0.8, 0.03)
+        });
+    }
+
+    #[test]
+    fn fits_pareto_ii() {
+        let data = samples(&ParetoII::new(1.8, 0.8));
+        let fit = pareto_ii(&data).unwrap();
+        assert_fits(&fit, &data, |d| {
+            close(d.shape(), 1.8, 0.1) && close(d.scale(), 0.8, 0.1)
+        });
+    }
+
+    #[test]
+    fn pareto_ii_rejects_light_tails() {
+        let data = samples(&Normal::new(10., 1.));
+        assert_eq!(pareto_ii(&data).err(), Some(FitError::NoConvergence));
+
+        // log-uniform over 12 orders of magnitude, the scale goes to 0
+        let data = (1..1000)
+            .map(|i| 1e-12_f64.powf(i as f64 / 1000.))
+            .collect::<Vec<f64>>();
+        assert_eq!(pareto_ii(&data).err(), Some(FitError::NoConvergence));
+    }
+
+    #[test]
+    fn fits_gamma() {
+        for (shape, scale) in [(0.5, 2.), (3., 0.5)] {
+            let data = samples(&Gamma::new(shape, scale));
+            let fit = gamma(&data).unwrap();
+            assert_fits(&fit, &data, |d| {
+                close(d.shape(), shape, 0.05) && close(d.scale(), scale, 0.05)
+            });
+        }
+    }
+
+    #[test]
+    fn fits_weibull() {
+        for (shape, scale) in [(0.7, 2.), (2.5, 0.5)] {
+            let data = samples(&Weibull::new(shape, scale));
+            let fit = weibull(&data).unwrap();
+            assert_fits(&fit, &data, |d| {
+                close(d.shape(), shape, 0.03) && close(d.scale(), scale, 0.03)
+            });
+        }
+    }
+
+    #[test]
+    fn fits_phase_type() {
+        let data = samples(&Hyperexponential::new(vec![0.2, 0.8], vec![4., 0.5]));
+        let fit = phase_type(&data).unwrap();
+        assert!(matches!(fit.distribution, PhaseType::Hyperexponential(_)));
+
+        let data = samples(&Gamma::new(2.5, 1.));
+        let fit = phase_type(&data).unwrap();
+        assert_fits(&fit, &data, |d| match d {
+            PhaseType::MixedErlang(d) => d.k() == 3,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn rejects_bad_data() {
+        assert_eq!(
+            exponential::<f64>(&[1.]).err(),
+            Some(FitError::NotEnoughData { minimum: 2 })
+        );
+        assert_eq!(gamma(&[1., -1.]).err(), Some(FitError::NonPositiveData));
+    }
+}
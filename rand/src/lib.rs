@@ -1,3 +1,8 @@
+pub mod analysis;
 pub mod distributions;
+pub mod fit;
+pub mod qmc;
 pub mod rngs;
+pub mod seq;
 pub mod series;
+pub mod variance;
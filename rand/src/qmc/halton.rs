@@ -0,0 +1,114 @@
+use crate::rngs::Rng;
+use crate::seq::SliceRandom;
+
+use super::LowDiscrepancy;
+
+/// Halton sequence, coordinate `j` is the radical inverse of the index in
+/// the `j`-th prime base.
+///
+/// The scrambled variant applies an independent random permutation to every
+/// digit of every coordinate (random-digit scrambling), which also breaks the
+/// correlation between coordinates with large bases.
+pub struct Halton {
+    bases: Vec<u64>,
+    // per coordinate, `digits * base` entries, the permutation of digit
+    // position `k` starts at `k * base`
+    permutations: Option<Vec<Vec<u64>>>,
+    index: u64,
+}
+
+impl Halton {
+    pub fn new(dimension: usize) -> Self {
+        assert!(dimension > 0, "the dimension should be at least 1");
+
+        let mut bases: Vec<u64> = Vec::with_capacity(dimension);
+        let mut candidate = 2;
+        while bases.len() < dimension {
+            if bases.iter().all(|p| candidate % p != 0) {
+                bases.push(candidate);
+            }
+            candidate += 1;
+        }
+
+        Self {
+            bases,
+            permutations: None,
+            index: 0,
+        }
+    }
+
+    pub fn scrambled<R>(dimension: usize, rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        let mut halton = Self::new(dimension);
+
+        let permutations = halton
+            .bases
+            .iter()
+            .map(|&base| {
+                let digits = digit_count(base);
+                let mut permutation = Vec::with_capacity(digits * base as usize);
+                for _ in 0..digits {
+                    let start = permutation.len();
+                    permutation.extend(0..base);
+                    permutation[start..].shuffle(rng);
+                }
+                permutation
+            })
+            .collect();
+
+        halton.permutations = Some(permutations);
+        halton
+    }
+}
+
+// enough digits to cover the 53 bits of an f64 mantissa
+fn digit_count(base: u64) -> usize {
+    let mut digits = 0;
+    let mut scale = 1.0;
+    while scale < 2_f64.powi(53) {
+        scale *= base as f64;
+        digits += 1;
+    }
+    digits
+}
+
+impl LowDiscrepancy for Halton {
+    fn dimension(&self) -> usize {
+        self.bases.len()
+    }
+
+    fn next_point(&mut self, point: &mut [u64]) {
+        for (j, (x, &base)) in point.iter_mut().zip(&self.bases).enumerate() {
+            let inv_base = (base as f64).recip();
+
+            let mut n = self.index;
+            let mut scale = inv_base;
+            let mut value = 0.0;
+
+            match &self.permutations {
+                None => {
+                    while n > 0 {
+                        value += (n % base) as f64 * scale;
+                        n /= base;
+                        scale *= inv_base;
+                    }
+                }
+                Some(permutations) => {
+                    // the leading zeros are permuted as well
+                    for digit in permutations[j].chunks_exact(base as usize) {
+                        value += digit[(n % base) as usize] as f64 * scale;
+                        n /= base;
+                        scale *= inv_base;
+                    }
+                }
+            }
+
+            // value < 1, the cast saturates in the unlikely case it rounds up
+            *x = (value * 2_f64.powi(64)) as u64;
+        }
+
+        self.index += 1;
+    }
+}
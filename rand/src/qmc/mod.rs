@@ -0,0 +1,178 @@
+use crate::rngs::{fill_from_u64, Rng};
+
+mod halton;
+mod sobol;
+
+pub use halton::Halton;
+pub use sobol::Sobol;
+
+pub trait LowDiscrepancy {
+    fn dimension(&self) -> usize;
+
+    // each coordinate is a 64-bit fixed-point fraction, i.e. x / 2^64
+    fn next_point(&mut self, point: &mut [u64]);
+}
+
+/// Exposes the coordinates of a low-discrepancy point set as an `Rng`, so
+/// that the inverse CDF samplers (`Exponential`, `ParetoII`, ...) can be
+/// driven by quasi-random points.
+///
+/// Every call to `next_u64` consumes one coordinate of the current point,
+/// and `next_point` moves on to the next point of the sequence. Note that
+/// unscrambled sequences start at the origin, which some samplers map to
+/// an infinite value.
+pub struct QmcRng<S> {
+    sequence: S,
+    point: Vec<u64>,
+    coordinate: usize,
+}
+
+impl<S> QmcRng<S>
+where
+    S: LowDiscrepancy,
+{
+    pub fn new(sequence: S) -> Self {
+        let dimension = sequence.dimension();
+
+        Self {
+            sequence,
+            point: vec![0; dimension],
+            coordinate: dimension,
+        }
+    }
+
+    pub fn next_point(&mut self) {
+        self.sequence.next_point(&mut self.point);
+        self.coordinate = 0;
+    }
+
+    fn next_coordinate(&mut self) -> u64 {
+        let x = *self
+            .point
+            .get(self.coordinate)
+            .expect("the current point should have coordinates left");
+        self.coordinate += 1;
+        x
+    }
+}
+
+impl<S> Rng for QmcRng<S>
+where
+    S: LowDiscrepancy,
+{
+    fn fill_bytes(&mut self, buf: &mut [u8]) {
+        fill_from_u64(buf, || self.next_coordinate())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::Exponential;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    #[test]
+    fn sobol_first_points() {
+        let mut sobol = Sobol::new(3);
+        let mut point = [0; 3];
+
+        for expected in [
+            [0.0, 0.0, 0.0],
+            [0.5, 0.5, 0.5],
+            [0.75, 0.25, 0.25],
+            [0.25, 0.75, 0.75],
+            [0.375, 0.375, 0.625],
+            [0.875, 0.875, 0.125],
+            [0.625, 0.125, 0.875],
+            [0.125, 0.625, 0.375],
+        ] {
+            sobol.next_point(&mut point);
+            assert_eq!(point.map(to_f64), expected);
+        }
+    }
+
+    #[test]
+    fn halton_first_points() {
+        let mut halton = Halton::new(2);
+        let mut point = [0; 2];
+
+        for expected in [
+            [0.0, 0.0],
+            [1. / 2., 1. / 3.],
+            [1. / 4., 2. / 3.],
+            [3. / 4., 1. / 9.],
+            [1. / 8., 4. / 9.],
+        ] {
+            halton.next_point(&mut point);
+            for (x, e) in point.into_iter().zip(expected) {
+                assert!((to_f64(x) - e).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn sobol_is_stratified() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let dimension = Sobol::MAX_DIMENSION;
+
+        for mut sobol in [Sobol::new(dimension), Sobol::scrambled(dimension, &mut rng)] {
+            for j in 0..dimension {
+                assert_stratified(&mut sobol, j, 2, 10);
+            }
+        }
+    }
+
+    #[test]
+    fn scrambled_halton_is_stratified() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+
+        assert_stratified(&mut Halton::scrambled(3, &mut rng), 0, 2, 10);
+        assert_stratified(&mut Halton::scrambled(3, &mut rng), 2, 5, 4);
+    }
+
+    #[test]
+    fn scrambled_sobol_exponential_mean() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut qmc = QmcRng::new(Sobol::scrambled(1, &mut rng));
+        let exponential = Exponential::new(2.0);
+
+        let n = 1 << 12;
+        let mut sum = 0.0;
+        for _ in 0..n {
+            qmc.next_point();
+            sum += qmc.sample(&exponential);
+        }
+
+        assert!((sum / n as f64 - 2.0).abs() < 1e-2);
+    }
+
+    fn to_f64(x: u64) -> f64 {
+        x as f64 / 2_f64.powi(64)
+    }
+
+    // the first base^m points fall one in each interval [k / base^m, (k+1) / base^m)
+    fn assert_stratified<S: LowDiscrepancy>(
+        sequence: &mut S,
+        coordinate: usize,
+        base: u64,
+        m: u32,
+    ) {
+        let n = base.pow(m) as usize;
+        let mut point = vec![0; sequence.dimension()];
+        let mut seen = vec![false; n];
+
+        for _ in 0..n {
+            sequence.next_point(&mut point);
+            let k = ((point[coordinate] as u128 * n as u128) >> 64) as usize;
+            assert!(!seen[k]);
+            seen[k] = true;
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::rngs::Rng;
+
+use super::LowDiscrepancy;
+
+const BITS: usize = 64;
+
+// https://web.maths.unsw.edu.au/~fkuo/sobol/
+// new-joe-kuo-6.21201, dimensions 2 onwards: (s, a, m_1..m_s)
+const JOE_KUO: [(u32, u64, &[u64]); 20] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+    (6, 19, &[1, 1, 1, 15, 7, 5]),
+    (6, 22, &[1, 3, 1, 15, 13, 25]),
+    (6, 25, &[1, 1, 5, 5, 19, 61]),
+    (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+    (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+];
+
+/// Sobol sequence with the Joe-Kuo direction numbers, generated in Gray code
+/// order.
+///
+/// The scrambled variant applies a random linear matrix scramble followed
+/// by a random digital shift, which keeps the net structure of the points
+/// while making each of them uniformly distributed.
+pub struct Sobol {
+    directions: Vec<[u64; BITS]>,
+    shift: Vec<u64>,
+    state: Vec<u64>,
+    index: u64,
+}
+
+impl Sobol {
+    pub const MAX_DIMENSION: usize = JOE_KUO.len() + 1;
+
+    pub fn new(dimension: usize) -> Self {
+        assert!(
+            (1..=Self::MAX_DIMENSION).contains(&dimension),
+            "the dimension should be between 1 and {}",
+            Self::MAX_DIMENSION,
+        );
+
+        let mut directions = Vec::with_capacity(dimension);
+
+        // first dimension is the van der Corput sequence in base 2
+        let mut v = [0; BITS];
+        for (k, v) in v.iter_mut().enumerate() {
+            *v = 1 << (BITS - 1 - k);
+        }
+        directions.push(v);
+
+        for &(s, a, m) in &JOE_KUO[..dimension - 1] {
+            let s = s as usize;
+            let mut v = [0; BITS];
+
+            for k in 0..s {
+                v[k] = m[k] << (BITS - 1 - k);
+            }
+            for k in s..BITS {
+                v[k] = v[k - s] ^ (v[k - s] >> s);
+                for j in 1..s {
+                    if (a >> (s - 1 - j)) & 1 == 1 {
+                        v[k] ^= v[k - j];
+                    }
+                }
+            }
+
+            directions.push(v);
+        }
+
+        Self {
+            directions,
+            shift: vec![0; dimension],
+            state: vec![0; dimension],
+            index: 0,
+        }
+    }
+
+    pub fn scrambled<R>(dimension: usize, rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        let mut sobol = Self::new(dimension);
+
+        for (v, shift) in sobol.directions.iter_mut().zip(&mut sobol.shift) {
+            // lower triangular with unit diagonal, bit 63 is the most
+            // significant digit, row i keeps digits 0..=i
+            let mut rows = [0; BITS];
+            for (i, row) in rows.iter_mut().enumerate() {
+                let mask = !0 << (BITS - 1 - i);
+                *row = (rng.next_u64() & mask) | 1 << (BITS - 1 - i);
+            }
+
+            for v in v.iter_mut() {
+                let mut scrambled = 0;
+                for (i, row) in rows.iter().enumerate() {
+                    let bit = (row & *v).count_ones() as u64 & 1;
+                    scrambled |= bit << (BITS - 1 - i);
+                }
+                *v = scrambled;
+            }
+
+            *shift = rng.next_u64();
+        }
+
+        sobol
+    }
+}
+
+impl LowDiscrepancy for Sobol {
+    fn dimension(&self) -> usize {
+        self.directions.len()
+    }
+
+    fn next_point(&mut self, point: &mut [u64]) {
+        for ((x, state), shift) in point.iter_mut().zip(&self.state).zip(&self.shift) {
+            *x = state ^ shift;
+        }
+
+        self.index += 1;
+        let c = self.index.trailing_zeros() as usize;
+        for (state, v) in self.state.iter_mut().zip(&self.directions) {
+            *state ^= v[c];
+        }
+    }
+}
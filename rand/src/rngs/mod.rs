@@ -40,6 +40,43 @@ pub trait Rng {
     }
 }
 
+pub trait Jump {
+    fn jump(&mut self);
+    fn long_jump(&mut self);
+}
+
+macro_rules! jump_impl {
+    ($($rng:ident),*) => {
+        $(
+            impl Jump for $rng {
+                fn jump(&mut self) {
+                    $rng::jump(self)
+                }
+
+                fn long_jump(&mut self) {
+                    $rng::long_jump(self)
+                }
+            }
+        )*
+    };
+}
+
+jump_impl! { Xoshiro128Plus, Xoshiro128PlusPlus, Xoshiro256Plus, Xoshiro256PlusPlus }
+
+// dedicated non-overlapping streams, the k-th one is `rng` jumped k times.
+// giving each random input of a model its own stream keeps the inputs
+// synchronized across scenarios (common random numbers)
+pub fn streams<R, const N: usize>(mut rng: R) -> [R; N]
+where
+    R: Jump + Clone,
+{
+    core::array::from_fn(|_| {
+        let stream = rng.clone();
+        rng.jump();
+        stream
+    })
+}
+
 pub fn fill_from_u32<F>(mut buf: &mut [u8], mut next: F)
 where
     F: FnMut() -> u32,
@@ -77,3 +114,33 @@ where
         buf.copy_from_slice(&chunk[..n]);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    #[test]
+    fn streams_are_jumps_apart() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut streams = streams::<_, 3>(rng.clone());
+
+        for stream in &mut streams {
+            let mut expected = rng.clone();
+            for _ in 0..10 {
+                assert_eq!(stream.next_u64(), expected.next_u64());
+            }
+            rng.jump();
+        }
+
+        // the streams do not start from the same state
+        let first = streams.map(|mut stream| stream.next_u64());
+        assert!(first[0] != first[1] && first[1] != first[2] && first[0] != first[2]);
+    }
+}
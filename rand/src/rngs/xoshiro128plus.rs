@@ -13,6 +13,7 @@ use super::{fill_from_u32, Rng};
 /// right shifts to extract subsets of bits.
 ///
 /// The state must be seeded so that it is not everywhere zero.
+#[derive(Clone)]
 pub struct Xoshiro128Plus {
     s: [u32; 4],
 }
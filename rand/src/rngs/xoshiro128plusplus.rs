@@ -10,6 +10,7 @@ use super::{fill_from_u32, Rng};
 /// numbers, xoshiro128+ is even faster.
 ///
 /// The state must be seeded so that it is not everywhere zero.
+#[derive(Clone)]
 pub struct Xoshiro128PlusPlus {
     s: [u32; 4],
 }
@@ -11,6 +11,7 @@ use super::{fill_from_u64, Rng};
 /// The state must be seeded so that it is not everywhere zero. If you have
 /// a 64-bit seed, we suggest to seed a splitmix64 generator and use its
 /// output to fill s.
+#[derive(Clone)]
 pub struct Xoshiro256PlusPlus {
     s: [u64; 4],
 }
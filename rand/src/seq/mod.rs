@@ -0,0 +1,203 @@
+use crate::distributions::{StandardUniformOpenOpen, UniformInt};
+use crate::rngs::Rng;
+
+pub trait SliceRandom {
+    type Item;
+
+    // Fisher-Yates
+    fn shuffle<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized;
+
+    fn choose<R>(&self, rng: &mut R) -> Option<&Self::Item>
+    where
+        R: Rng + ?Sized;
+
+    // `amount` distinct elements (all of them if there are fewer), in random order
+    fn choose_multiple<R>(&self, rng: &mut R, amount: usize) -> Vec<&Self::Item>
+    where
+        R: Rng + ?Sized;
+}
+
+impl<T> SliceRandom for [T] {
+    type Item = T;
+
+    fn shuffle<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+    {
+        for i in (1..self.len()).rev() {
+            let j = rng.sample(&UniformInt::<usize>::new(0, i + 1));
+            self.swap(i, j);
+        }
+    }
+
+    fn choose<R>(&self, rng: &mut R) -> Option<&T>
+    where
+        R: Rng + ?Sized,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        Some(&self[rng.sample(&UniformInt::<usize>::new(0, self.len()))])
+    }
+
+    fn choose_multiple<R>(&self, rng: &mut R, amount: usize) -> Vec<&T>
+    where
+        R: Rng + ?Sized,
+    {
+        // partial Fisher-Yates on the indices
+        let amount = amount.min(self.len());
+        let mut indices = (0..self.len()).collect::<Vec<_>>();
+        for i in 0..amount {
+            let j = rng.sample(&UniformInt::<usize>::new(i, self.len()));
+            indices.swap(i, j);
+        }
+        indices[..amount].iter().map(|&i| &self[i]).collect()
+    }
+}
+
+pub trait IteratorRandom: Iterator + Sized {
+    // single pass, one bounded integer per element
+    fn choose<R>(self, rng: &mut R) -> Option<Self::Item>
+    where
+        R: Rng + ?Sized,
+    {
+        let mut chosen = None;
+        for (i, item) in self.enumerate() {
+            if rng.sample(&UniformInt::<usize>::new(0, i + 1)) == 0 {
+                chosen = Some(item);
+            }
+        }
+        chosen
+    }
+
+    // reservoir sampling with Li's Algorithm L, a single pass that only
+    // draws random numbers when the reservoir changes, O(k (1 + ln(n/k)))
+    // draws in total. the result is not in random order
+    fn choose_multiple<R>(mut self, rng: &mut R, amount: usize) -> Vec<Self::Item>
+    where
+        R: Rng + ?Sized,
+    {
+        let mut reservoir = self.by_ref().take(amount).collect::<Vec<_>>();
+        if reservoir.len() < amount || amount == 0 {
+            return reservoir;
+        }
+
+        let k = amount as f64;
+        let next_w = |rng: &mut R| {
+            let u: f64 = rng.sample(&StandardUniformOpenOpen);
+            (u.ln() / k).exp()
+        };
+
+        let mut w = next_w(rng);
+        loop {
+            let u: f64 = rng.sample(&StandardUniformOpenOpen);
+            let skip = (u.ln() / (-w).ln_1p()).floor() as usize;
+
+            match self.nth(skip) {
+                Some(item) => {
+                    reservoir[rng.sample(&UniformInt::<usize>::new(0, amount))] = item;
+                    w *= next_w(rng);
+                }
+                None => return reservoir,
+            }
+        }
+    }
+}
+
+impl<I> IteratorRandom for I where I: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    fn chi_square(counts: &[usize], expected: f64) -> f64 {
+        counts
+            .iter()
+            .map(|&c| (c as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn shuffle_is_uniform() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let trials = 24000;
+
+        // the 24 permutations of 4 elements, indexed by their Lehmer code
+        let mut counts = [0; 24];
+        for _ in 0..trials {
+            let mut v = [0, 1, 2, 3];
+            v.shuffle(&mut rng);
+
+            let mut code = 0;
+            for i in 0..4 {
+                let smaller = v[i + 1..].iter().filter(|&&x| x < v[i]).count();
+                code = code * (4 - i) + smaller;
+            }
+            counts[code] += 1;
+        }
+
+        // 99.9% quantile of the chi-square distribution with 23 degrees of freedom
+        assert!(chi_square(&counts, trials as f64 / 24.) < 49.73);
+    }
+
+    #[test]
+    fn slice_choose_is_uniform() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let v = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let trials = 10000;
+
+        let mut counts = [0; 10];
+        for _ in 0..trials {
+            counts[*v.choose(&mut rng).unwrap()] += 1;
+        }
+        // 9 degrees of freedom
+        assert!(chi_square(&counts, trials as f64 / 10.) < 27.88);
+
+        let mut counts = [0; 10];
+        for _ in 0..trials {
+            let chosen = v.choose_multiple(&mut rng, 3);
+            assert_eq!(chosen.len(), 3);
+            assert!(chosen[0] != chosen[1] && chosen[1] != chosen[2] && chosen[0] != chosen[2]);
+            for &i in chosen {
+                counts[i] += 1;
+            }
+        }
+        assert!(chi_square(&counts, 3. * trials as f64 / 10.) < 27.88);
+    }
+
+    #[test]
+    fn reservoir_is_uniform() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let trials = 10000;
+
+        let mut counts = [0; 50];
+        for _ in 0..trials {
+            let chosen = (0..50).choose_multiple(&mut rng, 5);
+            assert_eq!(chosen.len(), 5);
+            for i in chosen {
+                counts[i] += 1;
+            }
+        }
+        // 99.9% quantile with 49 degrees of freedom
+        assert!(chi_square(&counts, 5. * trials as f64 / 50.) < 85.35);
+
+        let mut counts = [0; 10];
+        for _ in 0..trials {
+            counts[(0..10).choose(&mut rng).unwrap()] += 1;
+        }
+        assert!(chi_square(&counts, trials as f64 / 10.) < 27.88);
+
+        assert_eq!((0..3).choose_multiple(&mut rng, 5), vec![0, 1, 2]);
+        assert_eq!((0..0).choose(&mut rng), None::<usize>);
+    }
+}
@@ -0,0 +1,296 @@
+use num::poly::{mul, roots, roots_outside_unit_circle};
+use num::Float;
+
+use crate::distributions::Distribution;
+use crate::rngs::Rng;
+
+use super::{ArmaError, Stateful, TimeSeries};
+
+// seasonal part (P, D, Q)_s of a SARIMA model
+#[derive(Clone)]
+pub struct Seasonal<F> {
+    pub phi: Vec<F>,
+    pub d: usize,
+    pub theta: Vec<F>,
+    pub period: usize,
+}
+
+// ARIMA(p, d, q) x (P, D, Q)_s with orders chosen at runtime,
+//   phi(B) Phi(B^s) (w_t - drift) = theta(B) Theta(B^s) e_t
+//   w_t = (1 - B)^d (1 - B^s)^D x_t
+// where B is the backshift operator and e_t are i.i.d. innovations. the
+// drift is the mean of the differenced series w, i.e. the mean of x when
+// there is no differencing and the mean increment per step when d = 1
+#[derive(Clone)]
+pub struct ArimaModel<F, D> {
+    // the operators are expanded into plain lag polynomials:
+    // w_t = drift + sum ar_i (w_{t-i} - drift) + e_t + sum ma_j e_{t-j}
+    ar: Vec<F>,
+    ma: Vec<F>,
+    // (1 - B)^d (1 - B^s)^D = 1 + sum diff_k B^k
+    diff: Vec<F>,
+    drift: F,
+    innovation: D,
+    w: Vec<F>,
+    e: Vec<F>,
+    x: Vec<F>,
+}
+
+impl<F, D> ArimaModel<F, D>
+where
+    F: Float,
+{
+    pub fn new(
+        phi: Vec<F>,
+        d: usize,
+        theta: Vec<F>,
+        seasonal: Option<Seasonal<F>>,
+        drift: F,
+        innovation: D,
+    ) -> Result<Self, ArmaError<F>> {
+        let seasonal = seasonal.unwrap_or(Seasonal {
+            phi: Vec::new(),
+            d: 0,
+            theta: Vec::new(),
+            period: 1,
+        });
+        if seasonal.period == 0 {
+            return Err(ArmaError::ZeroPeriod);
+        }
+
+        // polynomials in B with the constant term first
+        let lags = |sign: F, c: &[F], period: usize| {
+            let mut p = vec![F::ZERO; c.len() * period + 1];
+            p[0] = F::ONE;
+            for (i, &c) in c.iter().enumerate() {
+                p[(i + 1) * period] = sign * c;
+            }
+            p
+        };
+
+        let ar = mul(
+            &lags(-F::ONE, &phi, 1),
+            &lags(-F::ONE, &seasonal.phi, seasonal.period),
+        );
+        let ma = mul(
+            &lags(F::ONE, &theta, 1),
+            &lags(F::ONE, &seasonal.theta, seasonal.period),
+        );
+
+        let mut diff = vec![F::ONE];
+        for _ in 0..d {
+            diff = mul(&diff, &lags(-F::ONE, &[F::ONE], 1));
+        }
+        for _ in 0..seasonal.d {
+            diff = mul(&diff, &lags(-F::ONE, &[F::ONE], seasonal.period));
+        }
+
+        if !roots_outside_unit_circle(&ar[1..]) {
+            return Err(ArmaError::NonStationary {
+                ar_roots: roots(&ar),
+            });
+        }
+        if !roots_outside_unit_circle(&ma[1..]) {
+            return Err(ArmaError::NonInvertible {
+                ma_roots: roots(&ma),
+            });
+        }
+
+        let ar = ar[1..].iter().map(|&c| -c).collect::<Vec<_>>();
+        let ma = ma[1..].to_vec();
+        let diff = diff[1..].to_vec();
+
+        Ok(Self {
+            w: vec![F::ZERO; ar.len()],
+            e: vec![F::ZERO; ma.len()],
+            x: vec![F::ZERO; diff.len()],
+            ar,
+            ma,
+            diff,
+            drift,
+            innovation,
+        })
+    }
+
+    // coefficients of w_{t-1}, w_{t-2}, ... after expanding the seasonal part
+    pub fn ar(&self) -> &[F] {
+        &self.ar
+    }
+
+    // coefficients of e_{t-1}, e_{t-2}, ... after expanding the seasonal part
+    pub fn ma(&self) -> &[F] {
+        &self.ma
+    }
+}
+
+// shifts `new` in as the most recent value
+fn push<F>(history: &mut [F], new: F)
+where
+    F: Float,
+{
+    if !history.is_empty() {
+        history.rotate_right(1);
+        history[0] = new;
+    }
+}
+
+impl<F, D> TimeSeries<F> for ArimaModel<F, D>
+where
+    F: Float,
+    D: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let new_e = rng.sample(&self.innovation);
+
+        let mut new_w = F::ZERO;
+        for (&ar, &w) in self.ar.iter().zip(&self.w) {
+            new_w += ar * w;
+        }
+        new_w += new_e;
+        for (&ma, &e) in self.ma.iter().zip(&self.e) {
+            new_w += ma * e;
+        }
+
+        // integrate, x_t = w_t - sum diff_k x_{t-k}
+        let mut new_x = new_w + self.drift;
+        for (&diff, &x) in self.diff.iter().zip(&self.x) {
+            new_x -= diff * x;
+        }
+
+        push(&mut self.w, new_w);
+        push(&mut self.e, new_e);
+        push(&mut self.x, new_x);
+
+        new_x
+    }
+}
+
+// the recent values of w, e and x, most recent first
+impl<F, D> Stateful for ArimaModel<F, D>
+where
+    F: Float,
+{
+    type State = (Vec<F>, Vec<F>, Vec<F>);
+
+    fn state(&self) -> Self::State {
+        (self.w.clone(), self.e.clone(), self.x.clone())
+    }
+
+    fn set_state(&mut self, (w, e, x): Self::State) {
+        assert!(
+            w.len() == self.w.len() && e.len() == self.e.len() && x.len() == self.x.len(),
+            "the state should have one value per lag"
+        );
+        self.w = w;
+        self.e = e;
+        self.x = x;
+    }
+
+    fn reset(&mut self) {
+        self.w.fill(F::ZERO);
+        self.e.fill(F::ZERO);
+        self.x.fill(F::ZERO);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::Normal;
+    use crate::rngs::Xoshiro256PlusPlus;
+    use crate::series::Arma;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    #[test]
+    fn matches_arma() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut arma = Arma::new([0.5, 0.2], [-0.3], 2., 5.).unwrap();
+
+        let mut rng2 = Xoshiro256PlusPlus::new(SEED);
+        let innovation = Normal::new(0., 2.);
+        let mut arima =
+            ArimaModel::new(vec![0.5, 0.2], 0, vec![-0.3], None, 5., innovation).unwrap();
+
+        for _ in 0..1000 {
+            let x = rng.get_next(&mut arma);
+            let y = rng2.get_next(&mut arima);
+            assert!((x - y).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn integrates_drift() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let innovation = Normal::new(0., 0.);
+
+        // x_t = x_{t-1} + 2
+        let mut arima = ArimaModel::new(vec![], 1, vec![], None, 2., innovation).unwrap();
+        for t in 1..10 {
+            assert_eq!(rng.get_next(&mut arima), 2. * t as f64);
+        }
+
+        // x_t = x_{t-4} + 1
+        let seasonal = Seasonal {
+            phi: vec![],
+            d: 1,
+            theta: vec![],
+            period: 4,
+        };
+        let innovation = Normal::new(0., 0.);
+        let mut arima = ArimaModel::new(vec![], 0, vec![], Some(seasonal), 1., innovation).unwrap();
+        for t in 0..12 {
+            assert_eq!(rng.get_next(&mut arima), (t / 4 + 1) as f64);
+        }
+    }
+
+    #[test]
+    fn expands_seasonal_operators() {
+        let seasonal = Seasonal {
+            phi: vec![0.5],
+            d: 0,
+            theta: vec![0.4],
+            period: 3,
+        };
+        let innovation = Normal::new(0., 1.);
+        let arima =
+            ArimaModel::new(vec![0.2], 0, vec![0.1], Some(seasonal), 0., innovation).unwrap();
+
+        // (1 - 0.2B)(1 - 0.5B^3) = 1 - 0.2B - 0.5B^3 + 0.1B^4
+        assert_eq!(arima.ar(), &[0.2, 0., 0.5, -0.1]);
+        // (1 + 0.1B)(1 + 0.4B^3) = 1 + 0.1B + 0.4B^3 + 0.04B^4
+        let expected = [0.1, 0., 0.4, 0.04];
+        for (ma, e) in arima.ma().iter().zip(expected) {
+            assert!((ma - e).abs() < 1e-15);
+        }
+
+        let seasonal = Seasonal {
+            phi: vec![1.1],
+            d: 0,
+            theta: vec![],
+            period: 12,
+        };
+        let innovation = Normal::new(0., 1.);
+        assert!(ArimaModel::new(vec![], 0, vec![], Some(seasonal), 0., innovation).is_err());
+
+        let seasonal = Seasonal {
+            phi: vec![0.5],
+            d: 1,
+            theta: vec![],
+            period: 0,
+        };
+        let innovation = Normal::new(0., 1.);
+        assert_eq!(
+            ArimaModel::new(vec![], 0, vec![], Some(seasonal), 0., innovation).err(),
+            Some(ArmaError::ZeroPeriod)
+        );
+    }
+}
@@ -1,16 +1,65 @@
+use core::fmt;
+
+use num::complex::Complex;
+use num::linalg::{levinson, LinalgError, Matrix};
+use num::poly::{roots, roots_outside_unit_circle};
+use num::special::normal_quantile;
 use num::Float;
 
-use crate::distributions::{Distribution, StandardNormal};
+use crate::distributions::{Distribution, Moments, MultivariateNormal, Normal, StandardNormal};
 use crate::rngs::Rng;
 
-use super::TimeSeries;
+use super::{Stateful, TimeSeries};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArmaError<F> {
+    // some root of the AR polynomial lies on or inside the unit circle
+    NonStationary { ar_roots: Vec<Complex<F>> },
+    // some root of the MA polynomial lies on or inside the unit circle
+    NonInvertible { ma_roots: Vec<Complex<F>> },
+    // the seasonal period of an ARIMA model is 0
+    ZeroPeriod,
+}
 
-pub struct Arma<F, const P: usize, const Q: usize> {
+impl<F> fmt::Display for ArmaError<F>
+where
+    F: Float + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (message, roots) = match self {
+            Self::NonStationary { ar_roots } => ("the AR part is not stationary", ar_roots),
+            Self::NonInvertible { ma_roots } => ("the MA part is not invertible", ma_roots),
+            Self::ZeroPeriod => return write!(f, "the seasonal period should be at least 1"),
+        };
+        write!(
+            f,
+            "{message}, the roots should lie outside the unit circle:"
+        )?;
+        for root in roots {
+            write!(f, " {root} (|z| = {})", root.norm())?;
+        }
+        Ok(())
+    }
+}
+
+impl<F> std::error::Error for ArmaError<F> where F: Float + fmt::Debug + fmt::Display {}
+
+/// ARMA(P, Q) process driven by i.i.d. innovations from `D`, Gaussian by
+/// default.
+///
+/// The series takes negative values whatever the innovations. For positive
+/// autocorrelated series, such as interarrival times, exponentiate a
+/// Gaussian ARMA (log-ARMA): `exp(x_t)` is log-normal with mean
+/// `exp(mean + gamma(0) / 2)` and autocorrelation
+/// `(exp(gamma(k)) - 1) / (exp(gamma(0)) - 1)`. `Arma::log_normal` picks the
+/// parameters from the mean and variance wanted for `exp(x_t)`.
+#[derive(Clone)]
+pub struct Arma<F, const P: usize, const Q: usize, D = Normal<F>> {
     phi: [F; P],
     z: [F; P],
     theta: [F; Q],
     e: [F; Q],
-    std_dev: F,
+    innovation: D,
     mean: F,
 }
 
@@ -18,48 +67,756 @@ impl<F, const P: usize, const Q: usize> Arma<F, P, Q>
 where
     F: Float,
 {
-    pub fn new(phi: [F; P], theta: [F; Q], std_dev: F, mean: F) -> Self {
-        Self {
+    // e_t ~ N(0, std_dev^2)
+    pub fn new(phi: [F; P], theta: [F; Q], std_dev: F, mean: F) -> Result<Self, ArmaError<F>> {
+        Self::with_innovation(phi, theta, Normal::new(F::ZERO, std_dev), mean)
+    }
+
+    // x_t such that exp(x_t) has the given mean and variance,
+    // Var x = ln(1 + variance / mean^2) and E x = ln(mean) - Var x / 2
+    pub fn log_normal(
+        phi: [F; P],
+        theta: [F; Q],
+        mean: F,
+        variance: F,
+    ) -> Result<Self, ArmaError<F>> {
+        let unit = Self::new(phi, theta, F::ONE, F::ZERO)?.variance();
+        let log_variance = (variance / (mean * mean)).ln_1p();
+        let std_dev = (log_variance / unit).sqrt();
+
+        Self::new(phi, theta, std_dev, mean.ln() - F::from(0.5) * log_variance)
+    }
+
+    // autocorrelation of exp(x_t), rho(0..=max_lag)
+    pub fn exp_autocorrelation(&self, max_lag: usize) -> Vec<F> {
+        let gamma = self.autocovariance(max_lag);
+        let denominator = gamma[0].exp() - F::ONE;
+        gamma
+            .iter()
+            .map(|&g| (g.exp() - F::ONE) / denominator)
+            .collect()
+    }
+
+    // replaces the state by a draw from its stationary distribution, so the
+    // series is stationary from the first sample and no warm-up is needed.
+    pub fn init_stationary<R>(&mut self, rng: &mut R) -> Result<(), LinalgError>
+    where
+        R: Rng + ?Sized,
+        StandardNormal: Distribution<F>,
+    {
+        let variance = self.innovation.variance();
+        let gamma = autocovariances(&self.phi, &self.theta, variance, P)?;
+        let psi = psi_weights(&self.phi, &self.theta, Q);
+
+        // state = (z_t, ..., z_{t-P+1}, e_t, ..., e_{t-Q+1})
+        // Cov(z_{t-i}, z_{t-j}) = gamma(|i - j|)
+        // Cov(z_{t-i}, e_{t-j}) = sigma^2 psi_{j-i} if j >= i, 0 otherwise
+        // Cov(e_{t-i}, e_{t-j}) = sigma^2 if i = j, 0 otherwise
+        let mut covariance = Matrix::zeros(P + Q, P + Q);
+        for i in 0..P {
+            for j in 0..P {
+                covariance[(i, j)] = gamma[i.abs_diff(j)];
+            }
+            for j in i..Q {
+                covariance[(i, P + j)] = variance * psi[j - i];
+                covariance[(P + j, i)] = variance * psi[j - i];
+            }
+        }
+        for j in 0..Q {
+            covariance[(P + j, P + j)] = variance;
+        }
+
+        // innovations with a non-zero mean shift z_t as well
+        let mut mean = vec![self.mean() - self.mean; P];
+        mean.extend(core::iter::repeat(self.innovation.mean()).take(Q));
+
+        let state = MultivariateNormal::new_semidefinite(mean, &covariance)?;
+        let state = rng.sample(&state);
+
+        self.z.copy_from_slice(&state[..P]);
+        self.e.copy_from_slice(&state[P..]);
+        Ok(())
+    }
+}
+
+impl<F, const P: usize, const Q: usize, D> Arma<F, P, Q, D>
+where
+    F: Float,
+{
+    // x_t - mean = sum phi_i (x_{t-i} - mean) + e_t + sum theta_j e_{t-j},
+    // the parameters should give a stationary and invertible process.
+    // `mean` is the mean of x_t only for zero mean innovations
+    pub fn with_innovation(
+        phi: [F; P],
+        theta: [F; Q],
+        innovation: D,
+        mean: F,
+    ) -> Result<Self, ArmaError<F>> {
+        let arma = Self {
             phi,
             z: [F::ZERO; P],
             theta,
             e: [F::ZERO; Q],
-            std_dev,
+            innovation,
             mean,
+        };
+
+        if !arma.is_stationary() {
+            return Err(ArmaError::NonStationary {
+                ar_roots: arma.ar_roots(),
+            });
+        }
+        if !arma.is_invertible() {
+            return Err(ArmaError::NonInvertible {
+                ma_roots: arma.ma_roots(),
+            });
+        }
+        Ok(arma)
+    }
+
+    // roots of 1 - phi_1 z - ... - phi_P z^P
+    pub fn ar_roots(&self) -> Vec<Complex<F>> {
+        let mut c = vec![F::ONE];
+        c.extend(self.phi.iter().map(|&phi| -phi));
+        roots(&c)
+    }
+
+    // roots of 1 + theta_1 z + ... + theta_Q z^Q
+    pub fn ma_roots(&self) -> Vec<Complex<F>> {
+        let mut c = vec![F::ONE];
+        c.extend(self.theta);
+        roots(&c)
+    }
+
+    pub fn is_stationary(&self) -> bool {
+        let c = self.phi.map(|phi| -phi);
+        roots_outside_unit_circle(&c)
+    }
+
+    pub fn is_invertible(&self) -> bool {
+        roots_outside_unit_circle(&self.theta)
+    }
+
+    pub fn innovation(&self) -> &D {
+        &self.innovation
+    }
+
+    pub(super) fn innovation_mut(&mut self) -> &mut D {
+        &mut self.innovation
+    }
+
+    // feeds the next innovation through the filter
+    pub(super) fn filter(&mut self, new_e: F) -> F {
+        step(&self.phi, &self.theta, &mut self.z, &mut self.e, new_e) + self.mean
+    }
+
+    // replaces the state by the one left by the observed values x_t, oldest
+    // first. The innovations are recovered by inverting the filter,
+    // e_t = z_t - sum phi_i z_{t-i} - sum theta_j e_{t-j}, from a zero state
+    // whose effect dies out as the process is invertible.
+    pub fn condition_on(&mut self, history: &[F]) {
+        self.z = [F::ZERO; P];
+        self.e = [F::ZERO; Q];
+        for &x in history {
+            let predicted = step(&self.phi, &self.theta, &mut self.z, &mut self.e, F::ZERO);
+            let new_e = x - self.mean - predicted;
+            if P > 0 {
+                self.z[0] += new_e;
+            }
+            if Q > 0 {
+                self.e[0] = new_e;
+            }
+        }
+    }
+
+    // a future path of `horizon` values from the current state, which is
+    // left unchanged so that many paths can start from the same history
+    pub fn sample_path<R>(&self, horizon: usize, rng: &mut R) -> Vec<F>
+    where
+        R: Rng + ?Sized,
+        D: Distribution<F>,
+    {
+        let (mut z, mut e) = (self.z, self.e);
+        (0..horizon)
+            .map(|_| {
+                let new_e = rng.sample(&self.innovation);
+                step(&self.phi, &self.theta, &mut z, &mut e, new_e) + self.mean
+            })
+            .collect()
+    }
+}
+
+// z_t = sum phi_i z_{t-i} + e_t + sum theta_j e_{t-j}, shifting the state
+fn step<F, const P: usize, const Q: usize>(
+    phi: &[F; P],
+    theta: &[F; Q],
+    z: &mut [F; P],
+    e: &mut [F; Q],
+    new_e: F,
+) -> F
+where
+    F: Float,
+{
+    let mut new_z = F::ZERO;
+
+    for i in 0..P {
+        new_z += phi[i] * z[i];
+    }
+    new_z += new_e;
+    for i in 0..Q {
+        new_z += theta[i] * e[i];
+    }
+
+    if P > 0 {
+        z.rotate_right(1);
+        z[0] = new_z;
+    }
+
+    if Q > 0 {
+        e.rotate_right(1);
+        e[0] = new_e;
+    }
+
+    new_z
+}
+
+/// Forecast of a future value: its conditional mean given the state, and the
+/// standard deviation of the forecast error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Forecast<F> {
+    pub mean: F,
+    pub std_error: F,
+}
+
+impl<F> Forecast<F>
+where
+    F: Float,
+{
+    // normal interval, exact for Gaussian innovations, e.g. level = 0.95
+    pub fn prediction_interval(&self, level: F) -> (F, F) {
+        let z = normal_quantile(F::from(0.5) + F::from(0.5) * level);
+        (
+            self.mean - z * self.std_error,
+            self.mean + z * self.std_error,
+        )
+    }
+}
+
+impl<F, const P: usize, const Q: usize, D> Arma<F, P, Q, D>
+where
+    F: Float,
+    D: Moments<F>,
+{
+    // mean + mean_e (1 + sum theta_j) / (1 - sum phi_i)
+    pub fn mean(&self) -> F {
+        let ar = self.phi.iter().fold(F::ONE, |acc, &phi| acc - phi);
+        let ma = self.theta.iter().fold(F::ONE, |acc, &theta| acc + theta);
+        self.mean + self.innovation.mean() * ma / ar
+    }
+
+    pub fn variance(&self) -> F {
+        self.autocovariance(0)[0]
+    }
+
+    // gamma(0..=max_lag)
+    pub fn autocovariance(&self, max_lag: usize) -> Vec<F> {
+        let variance = self.innovation.variance();
+        autocovariances(&self.phi, &self.theta, variance, max_lag)
+            .expect("a stationary process should have autocovariances")
+    }
+
+    // rho(0..=max_lag)
+    pub fn autocorrelation(&self, max_lag: usize) -> Vec<F> {
+        let gamma = self.autocovariance(max_lag);
+        gamma.iter().map(|&g| g / gamma[0]).collect()
+    }
+
+    // phi_kk for k = 1..=max_lag, by the Durbin-Levinson recursion
+    pub fn partial_autocorrelation(&self, max_lag: usize) -> Vec<F> {
+        levinson(&self.autocovariance(max_lag)).reflection
+    }
+
+    // f(w) = sigma^2 / (2 pi) |theta(e^(-iw))|^2 / |phi(e^(-iw))|^2 for w in
+    // [-pi, pi], so that gamma(k) is the integral of f(w) e^(ikw)
+    pub fn spectral_density(&self, omega: F) -> F {
+        let mut ar = Complex::from_real(F::ONE);
+        for (i, &phi) in self.phi.iter().enumerate() {
+            let z = Complex::from_polar(F::ONE, -omega * F::from((i + 1) as f64));
+            ar -= z.scale(phi);
+        }
+
+        let mut ma = Complex::from_real(F::ONE);
+        for (j, &theta) in self.theta.iter().enumerate() {
+            let z = Complex::from_polar(F::ONE, -omega * F::from((j + 1) as f64));
+            ma += z.scale(theta);
+        }
+
+        let variance = self.innovation.variance();
+        variance / (F::from(2.) * F::PI) * ma.norm_sqr() / ar.norm_sqr()
+    }
+
+    // forecasts of the next `horizon` values from the current state, the
+    // future innovations being replaced by their mean. The error after h
+    // steps is sum_{j<h} psi_j e_{t+h-j}, of variance sigma^2 sum psi_j^2.
+    pub fn forecast(&self, horizon: usize) -> Vec<Forecast<F>> {
+        let innovation_mean = self.innovation.mean();
+        let variance = self.innovation.variance();
+        let psi = psi_weights(&self.phi, &self.theta, horizon);
+
+        let (mut z, mut e) = (self.z, self.e);
+        let mut error_variance = F::ZERO;
+        (0..horizon)
+            .map(|h| {
+                error_variance += variance * psi[h] * psi[h];
+                Forecast {
+                    mean: step(&self.phi, &self.theta, &mut z, &mut e, innovation_mean) + self.mean,
+                    std_error: error_variance.sqrt(),
+                }
+            })
+            .collect()
+    }
+}
+
+// coefficients of the MA(infinity) representation z_t = sum psi_j e_{t-j},
+// up to lag `max_lag`
+fn psi_weights<F>(phi: &[F], theta: &[F], max_lag: usize) -> Vec<F>
+where
+    F: Float,
+{
+    let mut psi = Vec::with_capacity(max_lag + 1);
+    psi.push(F::ONE);
+    for j in 1..=max_lag {
+        let mut p = theta.get(j - 1).copied().unwrap_or(F::ZERO);
+        for k in 1..=j.min(phi.len()) {
+            p += phi[k - 1] * psi[j - k];
+        }
+        psi.push(p);
+    }
+    psi
+}
+
+// gamma(0..=max_lag), solving the first P + 1 equations
+// gamma(k) - sum_i phi_i gamma(|k - i|) = sigma^2 sum_{j=k}^{Q} theta_j psi_{j-k}
+// (theta_0 = 1) and using them as a recursion for larger lags
+fn autocovariances<F>(
+    phi: &[F],
+    theta: &[F],
+    variance: F,
+    max_lag: usize,
+) -> Result<Vec<F>, LinalgError>
+where
+    F: Float,
+{
+    let p = phi.len();
+    let q = theta.len();
+    let psi = psi_weights(phi, theta, q);
+
+    let rhs = |k: usize| {
+        let mut sum = F::ZERO;
+        for j in k..=q {
+            let theta_j = if j == 0 { F::ONE } else { theta[j - 1] };
+            sum += theta_j * psi[j - k];
+        }
+        variance * sum
+    };
+
+    let mut a = Matrix::identity(p + 1);
+    for k in 0..=p {
+        for i in 1..=p {
+            a[(k, k.abs_diff(i))] -= phi[i - 1];
         }
     }
+    let b = (0..=p).map(rhs).collect::<Vec<_>>();
+    let mut gamma = a.solve(&b)?;
+
+    if gamma[0] <= F::ZERO {
+        return Err(LinalgError::NotPositiveDefinite);
+    }
+
+    for k in p + 1..=max_lag {
+        let mut g = rhs(k);
+        for i in 1..=p {
+            g += phi[i - 1] * gamma[k - i];
+        }
+        gamma.push(g);
+    }
+    gamma.truncate(max_lag + 1);
+    Ok(gamma)
 }
 
-impl<F, const P: usize, const Q: usize> TimeSeries<F> for Arma<F, P, Q>
+impl<F, const P: usize, const Q: usize, D> TimeSeries<F> for Arma<F, P, Q, D>
 where
     F: Float,
-    StandardNormal: Distribution<F>,
+    D: Distribution<F>,
 {
     fn get_next<R>(&mut self, rng: &mut R) -> F
     where
         R: Rng + ?Sized,
     {
-        let mut new_z = F::ZERO;
-        let new_e = rng.sample(&StandardNormal) * self.std_dev;
+        let new_e = rng.sample(&self.innovation);
+        self.filter(new_e)
+    }
+}
 
-        for i in 0..P {
-            new_z += self.phi[i] * self.z[i];
+// (z_t, ..., z_{t-P+1}) and (e_t, ..., e_{t-Q+1}), the state of the
+// innovations themselves, if any, is left to the wrappers
+impl<F, const P: usize, const Q: usize, D> Stateful for Arma<F, P, Q, D>
+where
+    F: Float,
+{
+    type State = ([F; P], [F; Q]);
+
+    fn state(&self) -> Self::State {
+        (self.z, self.e)
+    }
+
+    fn set_state(&mut self, (z, e): Self::State) {
+        self.z = z;
+        self.e = e;
+    }
+
+    fn reset(&mut self) {
+        self.set_state(([F::ZERO; P], [F::ZERO; Q]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    #[test]
+    fn autocovariances_works() {
+        // ARMA(1, 1): gamma(0) = sigma^2 (1 + 2 phi theta + theta^2) / (1 - phi^2)
+        //             gamma(1) = sigma^2 (1 + phi theta) (phi + theta) / (1 - phi^2)
+        let (phi, theta, variance) = (0.7, -0.3, 3.);
+        let gamma = autocovariances(&[phi], &[theta], variance, 3).unwrap();
+
+        let expected_0 = variance * (1. + 2. * phi * theta + theta * theta) / (1. - phi * phi);
+        let expected_1 = variance * (1. + phi * theta) * (phi + theta) / (1. - phi * phi);
+        assert!((gamma[0] - expected_0).abs() < 1e-12);
+        assert!((gamma[1] - expected_1).abs() < 1e-12);
+        assert!((gamma[3] - phi * phi * expected_1).abs() < 1e-12);
+
+        // MA(2): gamma(k) = sigma^2 sum theta_j theta_{j+k}
+        let gamma = autocovariances(&[], &[0.5, 0.2], 1., 3).unwrap();
+        assert_eq!(gamma.len(), 4);
+        assert!((gamma[0] - 1.29).abs() < 1e-12);
+        assert!((gamma[1] - 0.6).abs() < 1e-12);
+        assert!((gamma[2] - 0.2).abs() < 1e-12);
+        assert!(gamma[3].abs() < 1e-12);
+
+        assert!(autocovariances(&[1.2], &[], 1., 1).is_err());
+    }
+
+    #[test]
+    fn init_stationary_is_stationary() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let (phi, theta, std_dev) = ([0.5, 0.3], [0.4], 2_f64);
+        let gamma = autocovariances(&phi, &theta, std_dev * std_dev, 1).unwrap();
+
+        // first and second samples of independent paths
+        let count = 40000;
+        let mut sum = [0.; 3];
+        for _ in 0..count {
+            let mut arma = Arma::new(phi, theta, std_dev, 0.).unwrap();
+            arma.init_stationary(&mut rng).unwrap();
+            let x0 = rng.get_next(&mut arma);
+            let x1 = rng.get_next(&mut arma);
+            sum[0] += x0 * x0;
+            sum[1] += x1 * x1;
+            sum[2] += x0 * x1;
+        }
+        let moments = sum.map(|s| s / count as f64);
+
+        assert!((moments[0] / gamma[0] - 1.).abs() < 0.03);
+        assert!((moments[1] / gamma[0] - 1.).abs() < 0.03);
+        assert!((moments[2] / gamma[1] - 1.).abs() < 0.05);
+    }
+
+    #[test]
+    fn matches_empirical_moments() {
+        // the model of sim/src/bin/arma.rs
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut arma = Arma::new([0.7], [-0.3], 3_f64.sqrt(), 5.).unwrap();
+        arma.init_stationary(&mut rng).unwrap();
+
+        let count = 200000;
+        let data = std::iter::repeat_with(|| rng.get_next(&mut arma))
+            .take(count)
+            .collect::<Vec<_>>();
+
+        let mean = data.iter().sum::<f64>() / count as f64;
+        let gamma = (0..=5)
+            .map(|k| {
+                let sum = data
+                    .iter()
+                    .zip(&data[k..])
+                    .map(|(x, y)| (x - mean) * (y - mean))
+                    .sum::<f64>();
+                sum / count as f64
+            })
+            .collect::<Vec<_>>();
+
+        assert!((mean - arma.mean()).abs() < 0.05);
+        assert!((gamma[0] / arma.variance() - 1.).abs() < 0.03);
+        for (k, rho) in arma.autocorrelation(5).into_iter().enumerate() {
+            assert!((gamma[k] / gamma[0] - rho).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn non_gaussian_innovations() {
+        use crate::distributions::{Exponential, Laplace, Shifted, StudentT};
+
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+
+        // zero mean skewed innovations, the mean of the series is unchanged
+        let innovation = Shifted::new(Exponential::new(2.), -2.);
+        let mut arma = Arma::with_innovation([0.7], [-0.3], innovation, 5.).unwrap();
+        assert_eq!(arma.mean(), 5.);
+        let variance = arma.variance();
+        check(&mut rng, &mut arma, 5., variance);
+
+        // E e_t = 1 adds (1 - 0.3) / (1 - 0.7) to the mean
+        let mut arma = Arma::with_innovation([0.7], [-0.3], Exponential::new(1.), 0.).unwrap();
+        assert!((arma.mean() - 7. / 3.).abs() < 1e-12);
+        let variance = arma.variance();
+        check(&mut rng, &mut arma, 7. / 3., variance);
+
+        let mut arma = Arma::with_innovation([0.5], [0.2], Laplace::new(0., 1.), 1.).unwrap();
+        let variance = arma.variance();
+        check(&mut rng, &mut arma, 1., variance);
+
+        let mut arma = Arma::with_innovation([0.5], [0.2], StudentT::new(6., 1.), 0.).unwrap();
+        let variance = arma.variance();
+        assert!((variance / arma.autocovariance(0)[0] - 1.).abs() < 1e-12);
+        check(&mut rng, &mut arma, 0., variance);
+    }
+
+    fn check<D>(
+        rng: &mut Xoshiro256PlusPlus,
+        arma: &mut Arma<f64, 1, 1, D>,
+        mean: f64,
+        variance: f64,
+    ) where
+        D: Distribution<f64>,
+    {
+        let count = 200000;
+        let data = std::iter::repeat_with(|| rng.get_next(arma))
+            .take(count)
+            .collect::<Vec<_>>();
+        let m = data.iter().sum::<f64>() / count as f64;
+        let v = data.iter().map(|x| (x - m) * (x - m)).sum::<f64>() / count as f64;
+
+        assert!((m - mean).abs() < 0.05);
+        assert!((v / variance - 1.).abs() < 0.05);
+    }
+
+    #[test]
+    fn log_normal_matches_moments() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let (mean, variance) = (5., 4.);
+        let mut arma = Arma::log_normal([0.7], [-0.3], mean, variance).unwrap();
+        arma.init_stationary(&mut rng).unwrap();
+
+        let count = 400000;
+        let data = std::iter::repeat_with(|| rng.get_next(&mut arma).exp())
+            .take(count)
+            .collect::<Vec<_>>();
+        assert!(data.iter().all(|&x| x > 0.));
+
+        let m = data.iter().sum::<f64>() / count as f64;
+        let gamma = (0..=3)
+            .map(|k| {
+                let sum = data
+                    .iter()
+                    .zip(&data[k..])
+                    .map(|(x, y)| (x - m) * (y - m))
+                    .sum::<f64>();
+                sum / count as f64
+            })
+            .collect::<Vec<_>>();
+
+        assert!((m - mean).abs() < 0.02);
+        assert!((gamma[0] / variance - 1.).abs() < 0.03);
+        for (k, rho) in arma.exp_autocorrelation(3).into_iter().enumerate() {
+            assert!((gamma[k] / gamma[0] - rho).abs() < 0.02);
         }
-        new_z += new_e;
-        for i in 0..Q {
-            new_z += self.theta[i] * self.e[i];
+    }
+
+    #[test]
+    fn partial_autocorrelation_cuts_off() {
+        let arma = Arma::new([0.5, 0.3], [], 1., 0.).unwrap();
+        let pacf = arma.partial_autocorrelation(4);
+
+        // rho(1) = phi_1 / (1 - phi_2)
+        assert!((pacf[0] - 0.5 / 0.7).abs() < 1e-12);
+        assert!((pacf[1] - 0.3).abs() < 1e-12);
+        assert!(pacf[2].abs() < 1e-12 && pacf[3].abs() < 1e-12);
+    }
+
+    #[test]
+    fn spectral_density_integrates_to_variance() {
+        let arma = Arma::new([0.5, 0.3], [0.4], 2., 0.).unwrap();
+
+        // trapezoidal rule over [-pi, pi], the integrand is periodic
+        let n = 4096;
+        let h = 2. * core::f64::consts::PI / n as f64;
+        let integral = (0..n)
+            .map(|i| arma.spectral_density(-core::f64::consts::PI + i as f64 * h) * h)
+            .sum::<f64>();
+
+        assert!((integral / arma.variance() - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pure_ma_works() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut arma = Arma::<f64, 0, 1>::new([], [0.5], 1., 0.).unwrap();
+
+        for _ in 0..10 {
+            assert!(rng.get_next(&mut arma).is_finite());
         }
 
-        for i in (1..P).rev() {
-            self.z[i] = self.z[i - 1];
+        let mut white = Arma::<f64, 0, 0>::new([], [], 1., 2.).unwrap();
+        assert!(rng.get_next(&mut white).is_finite());
+    }
+
+    #[test]
+    fn ar1_forecast() {
+        // x_{t+h} - mean = phi^h (x_t - mean), with error variance
+        // sigma^2 sum_{j<h} phi^2j
+        let (phi, std_dev, mean) = (0.8_f64, 2., 10.);
+        let mut arma = Arma::new([phi], [], std_dev, mean).unwrap();
+        arma.condition_on(&[12., 15.]);
+
+        let mut error_variance = 0.;
+        for (h, forecast) in arma.forecast(4).iter().enumerate() {
+            error_variance += std_dev * std_dev * phi.powi(2 * h as i32);
+            let expected = mean + 5. * phi.powi(h as i32 + 1);
+            assert!((forecast.mean - expected).abs() < 1e-12);
+            assert!((forecast.std_error - error_variance.sqrt()).abs() < 1e-12);
         }
-        self.z[0] = new_z;
 
-        for i in (1..Q).rev() {
-            self.e[i] = self.e[i - 1];
+        let (low, high) = arma.forecast(1)[0].prediction_interval(0.95);
+        assert!((low - (14. - 1.959964 * 2.)).abs() < 1e-5);
+        assert!((high - (14. + 1.959964 * 2.)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn condition_on_recovers_the_state() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let (phi, theta) = ([0.5, 0.3], [0.4]);
+        let mut arma = Arma::new(phi, theta, 1.5, 2.).unwrap();
+        arma.init_stationary(&mut rng).unwrap();
+        let history = std::iter::repeat_with(|| rng.get_next(&mut arma))
+            .take(200)
+            .collect::<Vec<f64>>();
+
+        let mut observer = Arma::new(phi, theta, 1.5, 2.).unwrap();
+        observer.condition_on(&history);
+        for (a, b) in arma.forecast(10).iter().zip(observer.forecast(10)) {
+            assert!((a.mean - b.mean).abs() < 1e-9);
+            assert_eq!(a.std_error, b.std_error);
         }
-        self.e[0] = new_e;
+    }
+
+    #[test]
+    fn sample_paths_match_forecast() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut arma = Arma::new([0.7], [-0.3], 1., 5.).unwrap();
+        arma.condition_on(&[5., 6., 8., 9.]);
+        let forecast = arma.forecast(5);
+
+        let count = 20000;
+        let (mut sum, mut sum_sq, mut covered) = ([0.; 5], [0.; 5], 0);
+        for _ in 0..count {
+            let path = arma.sample_path(5, &mut rng);
+            for h in 0..5 {
+                sum[h] += path[h];
+                sum_sq[h] += path[h] * path[h];
+            }
+            let (low, high) = forecast[4].prediction_interval(0.9);
+            if low < path[4] && path[4] < high {
+                covered += 1;
+            }
+        }
+
+        for h in 0..5 {
+            let mean = sum[h] / count as f64;
+            let std_dev = (sum_sq[h] / count as f64 - mean * mean).sqrt();
+            assert!((mean - forecast[h].mean).abs() < 0.03);
+            assert!((std_dev / forecast[h].std_error - 1.).abs() < 0.02);
+        }
+        assert!((covered as f64 / count as f64 - 0.9).abs() < 0.01);
+
+        // the paths leave the state untouched
+        assert_eq!(arma.forecast(5), forecast);
+    }
+
+    #[test]
+    fn state_forks_and_resets() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut arma = Arma::new([0.5, 0.3], [0.4], 1.5, 2.).unwrap();
+        let first = std::iter::repeat_with(|| rng.get_next(&mut arma))
+            .take(50)
+            .collect::<Vec<f64>>();
+
+        // a fork fed the same numbers follows the same path, whether it is a
+        // clone or a saved state restored later
+        let mut fork = arma.clone();
+        let state = arma.state();
+        let mut fork_rng = rng.clone();
+        let path = std::iter::repeat_with(|| rng.get_next(&mut arma))
+            .take(50)
+            .collect::<Vec<f64>>();
+        let mut replay_rng = fork_rng.clone();
+        for &x in &path {
+            assert_eq!(fork_rng.get_next(&mut fork), x);
+        }
+        arma.set_state(state);
+        for &x in &path {
+            assert_eq!(replay_rng.get_next(&mut arma), x);
+        }
+
+        // after a reset the model starts over as built
+        arma.reset();
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        for &x in &first {
+            assert_eq!(rng.get_next(&mut arma), x);
+        }
+    }
+
+    #[test]
+    fn new_checks_roots() {
+        assert!(Arma::new([0.7], [-0.3], 1., 0.).is_ok());
+
+        match Arma::new([1.2], [], 1., 0.) {
+            Err(ArmaError::NonStationary { ar_roots }) => {
+                assert_eq!(ar_roots.len(), 1);
+                assert!((ar_roots[0].re - 1. / 1.2).abs() < 1e-12);
+            }
+            _ => panic!("should not be stationary"),
+        }
+
+        // 1 - 0.5z - 0.6z^2 has a root at 0.94
+        assert!(matches!(
+            Arma::new([0.5, 0.6], [], 1., 0.),
+            Err(ArmaError::NonStationary { .. }),
+        ));
 
-        new_z + self.mean
+        // 1 + 2z has a root at -0.5
+        assert!(matches!(
+            Arma::new([0.5], [2.], 1., 0.),
+            Err(ArmaError::NonInvertible { .. }),
+        ));
     }
 }
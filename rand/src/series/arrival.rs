@@ -0,0 +1,331 @@
+use num::Float;
+
+use crate::distributions::{Distribution, Moments, StandardUniformOpenClosed};
+use crate::rngs::Rng;
+
+use super::{Stateful, TimeSeries};
+
+/// Source of arrivals for a queue: each call gives the time since the
+/// previous arrival and the number of clients arriving together (at least 1).
+pub trait ArrivalProcess<F> {
+    fn next_batch<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized;
+}
+
+/// Renewal process: i.i.d. interarrival times.
+#[derive(Clone)]
+pub struct Renewal<D> {
+    distribution: D,
+}
+
+impl<D> Renewal<D> {
+    pub fn new(distribution: D) -> Self {
+        Self { distribution }
+    }
+
+    pub fn distribution(&self) -> &D {
+        &self.distribution
+    }
+
+    pub fn rate<F>(&self) -> F
+    where
+        F: Float,
+        D: Moments<F>,
+    {
+        self.distribution.mean().recip()
+    }
+}
+
+impl<F, D> TimeSeries<F> for Renewal<D>
+where
+    D: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        rng.sample(&self.distribution)
+    }
+}
+
+impl<F, D> ArrivalProcess<F> for Renewal<D>
+where
+    D: Distribution<F>,
+{
+    fn next_batch<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized,
+    {
+        (rng.sample(&self.distribution), 1)
+    }
+}
+
+// the interarrival times are independent, there is nothing to keep
+impl<D> Stateful for Renewal<D> {
+    type State = ();
+
+    fn state(&self) {}
+
+    fn set_state(&mut self, _: ()) {}
+
+    fn reset(&mut self) {}
+}
+
+/// On/off source: Poisson arrivals with the given rate during the on periods
+/// and none during the off periods, the lengths of both being i.i.d. With
+/// heavy-tailed periods (infinite variance) the counts are long-range
+/// dependent.
+///
+/// The source starts at the beginning of an off period.
+#[derive(Clone)]
+pub struct OnOff<F, On, Off> {
+    on: On,
+    off: Off,
+    rate: F,
+    // time left in the current on period
+    remaining: F,
+}
+
+impl<F, On, Off> OnOff<F, On, Off>
+where
+    F: Float,
+    On: Distribution<F>,
+    Off: Distribution<F>,
+{
+    pub fn new(on: On, off: Off, rate: F) -> Self {
+        assert!(rate > F::ZERO, "the rate should be positive");
+        Self {
+            on,
+            off,
+            rate,
+            remaining: F::ZERO,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.remaining > F::ZERO
+    }
+}
+
+impl<F, On, Off> OnOff<F, On, Off>
+where
+    F: Float,
+    On: Moments<F>,
+    Off: Moments<F>,
+{
+    // rate times the fraction of time spent on
+    pub fn mean_rate(&self) -> F {
+        let on = self.on.mean();
+        self.rate * on / (on + self.off.mean())
+    }
+}
+
+impl<F, On, Off> TimeSeries<F> for OnOff<F, On, Off>
+where
+    F: Float,
+    On: Distribution<F>,
+    Off: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let mut time = F::ZERO;
+        loop {
+            // the exponential gap is memoryless, so an arrival falling after
+            // the on period can be discarded and drawn again in the next one
+            let u: F = rng.sample(&StandardUniformOpenClosed);
+            let gap = -u.ln() / self.rate;
+            if gap < self.remaining {
+                self.remaining -= gap;
+                return time + gap;
+            }
+
+            time += self.remaining + rng.sample(&self.off);
+            self.remaining = rng.sample(&self.on);
+        }
+    }
+}
+
+// the time left in the current on period, 0 when off
+impl<F, On, Off> Stateful for OnOff<F, On, Off>
+where
+    F: Float,
+{
+    type State = F;
+
+    fn state(&self) -> F {
+        self.remaining
+    }
+
+    fn set_state(&mut self, remaining: F) {
+        assert!(remaining >= F::ZERO, "the time left should be non-negative");
+        self.remaining = remaining;
+    }
+
+    fn reset(&mut self) {
+        self.remaining = F::ZERO;
+    }
+}
+
+impl<F, On, Off> ArrivalProcess<F> for OnOff<F, On, Off>
+where
+    F: Float,
+    On: Distribution<F>,
+    Off: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn next_batch<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized,
+    {
+        (self.get_next(rng), 1)
+    }
+}
+
+/// Batches of clients arriving together, the batch sizes being i.i.d. and
+/// independent of the interarrival times. Batches of size 0 are skipped,
+/// their interarrival time adding to the next one.
+#[derive(Clone)]
+pub struct BatchArrivals<S, B> {
+    interarrivals: S,
+    batch_size: B,
+}
+
+impl<S, B> BatchArrivals<S, B> {
+    pub fn new(interarrivals: S, batch_size: B) -> Self {
+        Self {
+            interarrivals,
+            batch_size,
+        }
+    }
+
+    pub fn interarrivals(&self) -> &S {
+        &self.interarrivals
+    }
+
+    pub fn batch_size(&self) -> &B {
+        &self.batch_size
+    }
+}
+
+impl<F, S, B> TimeSeries<(F, usize)> for BatchArrivals<S, B>
+where
+    F: Float,
+    S: TimeSeries<F>,
+    B: Distribution<usize>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized,
+    {
+        let mut time = F::ZERO;
+        loop {
+            time += self.interarrivals.get_next(rng);
+            let batch = self.batch_size.sample(rng);
+            if batch > 0 {
+                return (time, batch);
+            }
+        }
+    }
+}
+
+// the batch sizes are independent, only the interarrival times have a state
+impl<S, B> Stateful for BatchArrivals<S, B>
+where
+    S: Stateful,
+{
+    type State = S::State;
+
+    fn state(&self) -> S::State {
+        self.interarrivals.state()
+    }
+
+    fn set_state(&mut self, state: S::State) {
+        self.interarrivals.set_state(state);
+    }
+
+    fn reset(&mut self) {
+        self.interarrivals.reset();
+    }
+}
+
+impl<F, S, B> ArrivalProcess<F> for BatchArrivals<S, B>
+where
+    F: Float,
+    S: TimeSeries<F>,
+    B: Distribution<usize>,
+{
+    fn next_batch<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized,
+    {
+        self.get_next(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::{Exponential, UniformInt};
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    // clients per unit of time over n batches
+    fn mean_rate<A: ArrivalProcess<f64>>(arrivals: &mut A, n: usize) -> f64 {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let (mut time, mut count) = (0., 0);
+        for _ in 0..n {
+            let (interarrival, batch) = arrivals.next_batch(&mut rng);
+            time += interarrival;
+            count += batch;
+        }
+        count as f64 / time
+    }
+
+    #[test]
+    fn renewal() {
+        let mut renewal = Renewal::new(Exponential::new(0.5));
+        assert_eq!(renewal.rate::<f64>(), 2.);
+        assert!((mean_rate(&mut renewal, 100000) / 2. - 1.).abs() < 0.01);
+    }
+
+    #[test]
+    fn on_off() {
+        let mut source = OnOff::new(Exponential::new(1.), Exponential::new(3.), 8.);
+        assert_eq!(source.mean_rate(), 2.);
+        assert!(!source.is_on());
+        assert!((mean_rate(&mut source, 400000) / 2. - 1.).abs() < 0.02);
+    }
+
+    #[test]
+    fn batches() {
+        // batch sizes uniform in 0..4, the empty ones being skipped
+        let mut arrivals = BatchArrivals::new(
+            Renewal::new(Exponential::new(1.)),
+            UniformInt::<usize>::new(0, 4),
+        );
+
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let n = 100000;
+        let (mut time, mut count) = (0., 0);
+        for _ in 0..n {
+            let (interarrival, batch) = arrivals.next_batch(&mut rng);
+            assert!((1..4).contains(&batch));
+            time += interarrival;
+            count += batch;
+        }
+        // 4/3 time units and 2 clients per batch
+        assert!((time / n as f64 / (4. / 3.) - 1.).abs() < 0.01);
+        assert!((count as f64 / n as f64 / 2. - 1.).abs() < 0.01);
+        assert!((mean_rate(&mut arrivals, n) / 1.5 - 1.).abs() < 0.01);
+    }
+}
@@ -0,0 +1,312 @@
+use num::Float;
+
+use crate::distributions::{Distribution, StandardUniformClosedOpen, UniformInt};
+use crate::rngs::Rng;
+
+use super::{Stateful, TimeSeries};
+
+// how the blocks of consecutive values are drawn from a series of n values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Block<F> {
+    // `len` values from a start uniform in 0..=n-len, never wrapping
+    Moving { len: usize },
+    // `len` values from a start uniform in 0..n, wrapping around the end
+    Circular { len: usize },
+    // geometric lengths with the given mean, wrapping around the end
+    // (Politis and Romano), so that the resampled series is stationary
+    Stationary { mean_len: F },
+}
+
+// the indices of the values emitted by a block bootstrap
+#[derive(Clone)]
+pub(super) struct Blocks<F> {
+    block: Block<F>,
+    n: usize,
+    start: UniformInt<usize>,
+    // None until the first sample
+    position: Option<usize>,
+    // values left in the current block, for the fixed lengths
+    left: usize,
+}
+
+impl<F> Blocks<F>
+where
+    F: Float,
+{
+    pub(super) fn new(block: Block<F>, n: usize) -> Self {
+        assert!(n >= 1, "there should be at least one value");
+        let starts = match block {
+            Block::Moving { len } => {
+                assert!(
+                    1 <= len && len <= n,
+                    "the blocks should hold between 1 and n values"
+                );
+                n - len + 1
+            }
+            Block::Circular { len } => {
+                assert!(len >= 1, "the blocks should not be empty");
+                n
+            }
+            Block::Stationary { mean_len } => {
+                assert!(mean_len >= F::ONE, "the mean length should be at least 1");
+                n
+            }
+        };
+
+        Self {
+            block,
+            n,
+            start: UniformInt::<usize>::new(0, starts),
+            position: None,
+            left: 0,
+        }
+    }
+
+    pub(super) fn next_index<R>(&mut self, rng: &mut R) -> usize
+    where
+        R: Rng + ?Sized,
+        StandardUniformClosedOpen: Distribution<F>,
+    {
+        let new_block = match self.block {
+            Block::Moving { len } | Block::Circular { len } => {
+                let new_block = self.left == 0;
+                if new_block {
+                    self.left = len;
+                }
+                self.left -= 1;
+                new_block
+            }
+            // a block ends after each value with probability 1 / mean_len
+            Block::Stationary { mean_len } => {
+                let u: F = rng.sample(&StandardUniformClosedOpen);
+                self.position.is_none() || u * mean_len < F::ONE
+            }
+        };
+
+        let index = match self.position {
+            Some(position) if !new_block => position,
+            _ => rng.sample(&self.start),
+        };
+        self.position = Some(if index + 1 == self.n { 0 } else { index + 1 });
+        index
+    }
+}
+
+// the index of the next value, None before the first sample, and the values
+// left in the current block
+impl<F> Stateful for Blocks<F> {
+    type State = (Option<usize>, usize);
+
+    fn state(&self) -> Self::State {
+        (self.position, self.left)
+    }
+
+    fn set_state(&mut self, (position, left): Self::State) {
+        assert!(
+            position.map_or(true, |position| position < self.n),
+            "the position should be in the series"
+        );
+        self.position = position;
+        self.left = left;
+    }
+
+    fn reset(&mut self) {
+        self.set_state((None, 0));
+    }
+}
+
+/// Block bootstrap of a recorded series: the values are replayed in blocks
+/// of consecutive values starting at random points, which keeps the
+/// dependence within blocks.
+#[derive(Clone)]
+pub struct Bootstrap<F, V = Vec<F>> {
+    values: V,
+    blocks: Blocks<F>,
+}
+
+impl<F, V> Bootstrap<F, V>
+where
+    F: Float,
+    V: AsRef<[F]>,
+{
+    pub fn new(values: V, block: Block<F>) -> Self {
+        let n = values.as_ref().len();
+        Self {
+            values,
+            blocks: Blocks::new(block, n),
+        }
+    }
+
+    pub fn values(&self) -> &[F] {
+        self.values.as_ref()
+    }
+
+    // a resampled series of the same length as the original one
+    pub fn resample<R>(&mut self, rng: &mut R) -> Vec<F>
+    where
+        R: Rng + ?Sized,
+        StandardUniformClosedOpen: Distribution<F>,
+    {
+        // every series starts with a new block
+        self.blocks.reset();
+        (0..self.blocks.n).map(|_| self.get_next(rng)).collect()
+    }
+}
+
+impl<F, V> TimeSeries<F> for Bootstrap<F, V>
+where
+    F: Float,
+    V: AsRef<[F]>,
+    StandardUniformClosedOpen: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let index = self.blocks.next_index(rng);
+        self.values.as_ref()[index]
+    }
+}
+
+impl<F, V> Stateful for Bootstrap<F, V> {
+    type State = (Option<usize>, usize);
+
+    fn state(&self) -> Self::State {
+        self.blocks.state()
+    }
+
+    fn set_state(&mut self, state: Self::State) {
+        self.blocks.set_state(state);
+    }
+
+    fn reset(&mut self) {
+        self.blocks.reset();
+    }
+}
+
+// percentile interval of a statistic over `resamples` bootstrapped series,
+// e.g. level = 0.95
+pub fn bootstrap_interval<F, G, R>(
+    values: &[F],
+    block: Block<F>,
+    mut statistic: G,
+    resamples: usize,
+    level: F,
+    rng: &mut R,
+) -> (F, F)
+where
+    F: Float,
+    G: FnMut(&[F]) -> F,
+    R: Rng + ?Sized,
+    StandardUniformClosedOpen: Distribution<F>,
+{
+    assert!(resamples >= 1, "there should be at least one resample");
+
+    let mut bootstrap = Bootstrap::new(values, block);
+    let mut statistics = (0..resamples)
+        .map(|_| statistic(&bootstrap.resample(rng)))
+        .collect::<Vec<_>>();
+    statistics.sort_by(F::total_cmp);
+
+    // first index with k >= tail * resamples, and its mirror
+    let tail = F::from(0.5) * (F::ONE - level) * F::from(resamples as f64);
+    let low = (0..resamples)
+        .position(|k| F::from(k as f64) >= tail)
+        .unwrap_or(resamples - 1);
+    (statistics[low], statistics[resamples - 1 - low])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+    use crate::series::Arma;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    fn mean(data: &[f64]) -> f64 {
+        data.iter().sum::<f64>() / data.len() as f64
+    }
+
+    // lengths of the runs of consecutive indices
+    fn block_lengths(block: Block<f64>, n: usize, count: usize) -> Vec<usize> {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let values = (0..n).map(|i| i as f64).collect::<Vec<_>>();
+        let mut bootstrap = Bootstrap::new(values, block);
+
+        let mut lengths = vec![1];
+        let mut previous: f64 = bootstrap.get_next(&mut rng);
+        for _ in 1..count {
+            let x: f64 = bootstrap.get_next(&mut rng);
+            if x == (previous + 1.) % n as f64 {
+                *lengths.last_mut().unwrap() += 1;
+            } else {
+                lengths.push(1);
+            }
+            previous = x;
+        }
+        lengths
+    }
+
+    #[test]
+    fn block_schemes() {
+        // runs can merge when a block starts right after the previous one,
+        // so they are at least as long as the blocks
+        let lengths = block_lengths(Block::Moving { len: 5 }, 1000, 100000);
+        assert!(lengths.iter().all(|&len| len % 5 == 0));
+        let lengths = block_lengths(Block::Circular { len: 5 }, 1000, 100000);
+        assert!(lengths.iter().all(|&len| len % 5 == 0));
+
+        let lengths = block_lengths(Block::Stationary { mean_len: 8. }, 1000, 200000);
+        let mean_len = 200000. / lengths.len() as f64;
+        assert!((mean_len / 8. - 1.).abs() < 0.03);
+
+        // the moving blocks never wrap, so the last value is rarely drawn
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let values = (0..10).map(|i| i as f64).collect::<Vec<_>>();
+        let mut moving = Bootstrap::new(&values, Block::Moving { len: 4 });
+        let mut counts = [0; 10];
+        for _ in 0..70000 {
+            let x: f64 = moving.get_next(&mut rng);
+            counts[x as usize] += 1;
+        }
+        // value i is in min(i + 1, 4, 10 - i) of the 7 blocks
+        for (i, &count) in counts.iter().enumerate() {
+            let expected = (i + 1).min(4).min(10 - i) as f64 / 7. * 70000. / 4.;
+            assert!((count as f64 / expected - 1.).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn interval_of_the_mean() {
+        // AR(1) with phi = 0.8: the variance of the mean is inflated by
+        // (1 + phi) / (1 - phi) = 9 compared to i.i.d. values
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut arma = Arma::new([0.8], [], 0.6, 0.).unwrap();
+        arma.init_stationary(&mut rng).unwrap();
+        let n = 4000;
+        let data = std::iter::repeat_with(|| rng.get_next(&mut arma))
+            .take(n)
+            .collect::<Vec<f64>>();
+
+        let std_error = (9. / n as f64).sqrt();
+        let block = Block::Stationary { mean_len: 50. };
+        let (low, high) = bootstrap_interval(&data, block, mean, 1000, 0.95, &mut rng);
+        let half_width = 0.5 * (high - low);
+        assert!(low < mean(&data) && mean(&data) < high);
+        assert!(
+            (half_width / (1.96 * std_error) - 1.).abs() < 0.2,
+            "{half_width}"
+        );
+
+        // blocks of one value ignore the dependence
+        let block = Block::Circular { len: 1 };
+        let (low, high) = bootstrap_interval(&data, block, mean, 1000, 0.95, &mut rng);
+        assert!(0.5 * (high - low) < 0.5 * half_width);
+    }
+}
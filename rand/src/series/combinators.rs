@@ -0,0 +1,505 @@
+use core::marker::PhantomData;
+
+use num::Float;
+
+use crate::distributions::{Distribution, StandardUniformClosedOpen};
+use crate::rngs::Rng;
+
+use super::{Stateful, TimeSeries};
+
+/// I.i.d. samples of a distribution, as a series.
+#[derive(Clone)]
+pub struct FromDistribution<D> {
+    distribution: D,
+}
+
+impl<D> FromDistribution<D> {
+    pub fn new(distribution: D) -> Self {
+        Self { distribution }
+    }
+
+    pub fn distribution(&self) -> &D {
+        &self.distribution
+    }
+}
+
+impl<T, D> TimeSeries<T> for FromDistribution<D>
+where
+    D: Distribution<T>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> T
+    where
+        R: Rng + ?Sized,
+    {
+        rng.sample(&self.distribution)
+    }
+}
+
+impl<D> Stateful for FromDistribution<D> {
+    type State = ();
+
+    fn state(&self) {}
+
+    fn set_state(&mut self, _: ()) {}
+
+    fn reset(&mut self) {}
+}
+
+// the combinators below are built by the methods of `TimeSeries`
+
+#[derive(Clone)]
+pub struct Mapped<S, G, T> {
+    series: S,
+    f: G,
+    // the input type, which the impl could not name otherwise
+    input: PhantomData<fn() -> T>,
+}
+
+impl<S, G, T> Mapped<S, G, T> {
+    pub(super) fn new(series: S, f: G) -> Self {
+        Self {
+            series,
+            f,
+            input: PhantomData,
+        }
+    }
+}
+
+impl<S, G, T, U> TimeSeries<U> for Mapped<S, G, T>
+where
+    S: TimeSeries<T>,
+    G: FnMut(T) -> U,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> U
+    where
+        R: Rng + ?Sized,
+    {
+        (self.f)(self.series.get_next(rng))
+    }
+}
+
+#[derive(Clone)]
+pub struct Abs<S> {
+    series: S,
+}
+
+impl<S> Abs<S> {
+    pub(super) fn new(series: S) -> Self {
+        Self { series }
+    }
+}
+
+impl<F, S> TimeSeries<F> for Abs<S>
+where
+    F: Float,
+    S: TimeSeries<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        self.series.get_next(rng).abs()
+    }
+}
+
+#[derive(Clone)]
+pub struct Scale<S, F> {
+    series: S,
+    factor: F,
+}
+
+impl<S, F> Scale<S, F> {
+    pub(super) fn new(series: S, factor: F) -> Self {
+        Self { series, factor }
+    }
+}
+
+impl<F, S> TimeSeries<F> for Scale<S, F>
+where
+    F: Float,
+    S: TimeSeries<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        self.series.get_next(rng) * self.factor
+    }
+}
+
+#[derive(Clone)]
+pub struct Shift<S, F> {
+    series: S,
+    offset: F,
+}
+
+impl<S, F> Shift<S, F> {
+    pub(super) fn new(series: S, offset: F) -> Self {
+        Self { series, offset }
+    }
+}
+
+impl<F, S> TimeSeries<F> for Shift<S, F>
+where
+    F: Float,
+    S: TimeSeries<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        self.series.get_next(rng) + self.offset
+    }
+}
+
+#[derive(Clone)]
+pub struct ClampMin<S, F> {
+    series: S,
+    min: F,
+}
+
+impl<S, F> ClampMin<S, F> {
+    pub(super) fn new(series: S, min: F) -> Self {
+        Self { series, min }
+    }
+}
+
+impl<F, S> TimeSeries<F> for ClampMin<S, F>
+where
+    F: Float,
+    S: TimeSeries<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        self.series.get_next(rng).max(self.min)
+    }
+}
+
+#[derive(Clone)]
+pub struct Zip<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Zip<A, B> {
+    pub(super) fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<A, B, T, U> TimeSeries<(T, U)> for Zip<A, B>
+where
+    A: TimeSeries<T>,
+    B: TimeSeries<U>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> (T, U)
+    where
+        R: Rng + ?Sized,
+    {
+        let a = self.a.get_next(rng);
+        (a, self.b.get_next(rng))
+    }
+}
+
+impl<A, B> Stateful for Zip<A, B>
+where
+    A: Stateful,
+    B: Stateful,
+{
+    type State = (A::State, B::State);
+
+    fn state(&self) -> Self::State {
+        (self.a.state(), self.b.state())
+    }
+
+    fn set_state(&mut self, (a, b): Self::State) {
+        self.a.set_state(a);
+        self.b.set_state(b);
+    }
+
+    fn reset(&mut self) {
+        self.a.reset();
+        self.b.reset();
+    }
+}
+
+#[derive(Clone)]
+pub struct Aggregate<S> {
+    series: S,
+    k: usize,
+}
+
+impl<S> Aggregate<S> {
+    pub(super) fn new(series: S, k: usize) -> Self {
+        assert!(k >= 1, "at least one value should be aggregated");
+        Self { series, k }
+    }
+}
+
+impl<F, S> TimeSeries<F> for Aggregate<S>
+where
+    F: Float,
+    S: TimeSeries<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let mut sum = F::ZERO;
+        for _ in 0..self.k {
+            sum += self.series.get_next(rng);
+        }
+        sum
+    }
+}
+
+// the interarrival times of the merged stream, from the time left until the
+// next arrival of each stream
+#[derive(Clone)]
+pub struct Superpose<A, B, F> {
+    a: A,
+    b: B,
+    // None until the first sample
+    left: Option<(F, F)>,
+}
+
+impl<A, B, F> Superpose<A, B, F> {
+    pub(super) fn new(a: A, b: B) -> Self {
+        Self { a, b, left: None }
+    }
+}
+
+impl<F, A, B> TimeSeries<F> for Superpose<A, B, F>
+where
+    F: Float,
+    A: TimeSeries<F>,
+    B: TimeSeries<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let (a, b) = match self.left {
+            Some(left) => left,
+            None => {
+                let a = self.a.get_next(rng);
+                (a, self.b.get_next(rng))
+            }
+        };
+
+        let (interarrival, left) = if a <= b {
+            (a, (self.a.get_next(rng), b - a))
+        } else {
+            (b, (a - b, self.b.get_next(rng)))
+        };
+        self.left = Some(left);
+        interarrival
+    }
+}
+
+// the states of both streams and the time left until their next arrivals
+impl<A, B, F> Stateful for Superpose<A, B, F>
+where
+    A: Stateful,
+    B: Stateful,
+    F: Copy,
+{
+    type State = (A::State, B::State, Option<(F, F)>);
+
+    fn state(&self) -> Self::State {
+        (self.a.state(), self.b.state(), self.left)
+    }
+
+    fn set_state(&mut self, (a, b, left): Self::State) {
+        self.a.set_state(a);
+        self.b.set_state(b);
+        self.left = left;
+    }
+
+    fn reset(&mut self) {
+        self.a.reset();
+        self.b.reset();
+        self.left = None;
+    }
+}
+
+// each arrival is kept with probability p, the interarrival times of the
+// dropped ones adding up
+#[derive(Clone)]
+pub struct Thin<S, F> {
+    series: S,
+    p: F,
+}
+
+impl<S, F> Thin<S, F>
+where
+    F: Float,
+{
+    pub(super) fn new(series: S, p: F) -> Self {
+        assert!(
+            p > F::ZERO && p <= F::ONE,
+            "the probability should be in (0, 1]"
+        );
+        Self { series, p }
+    }
+}
+
+impl<F, S> TimeSeries<F> for Thin<S, F>
+where
+    F: Float,
+    S: TimeSeries<F>,
+    StandardUniformClosedOpen: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let mut time = F::ZERO;
+        loop {
+            time += self.series.get_next(rng);
+            let u: F = rng.sample(&StandardUniformClosedOpen);
+            if u < self.p {
+                return time;
+            }
+        }
+    }
+}
+
+// the combinators of a single series have no state of their own
+macro_rules! stateful_impl {
+    ($($combinator:ident<S $(, $param:ident)*>),*) => {
+        $(
+            impl<S $(, $param)*> Stateful for $combinator<S $(, $param)*>
+            where
+                S: Stateful,
+            {
+                type State = S::State;
+
+                fn state(&self) -> S::State {
+                    self.series.state()
+                }
+
+                fn set_state(&mut self, state: S::State) {
+                    self.series.set_state(state);
+                }
+
+                fn reset(&mut self) {
+                    self.series.reset();
+                }
+            }
+        )*
+    };
+}
+
+stateful_impl! {
+    Mapped<S, G, T>, Abs<S>, Scale<S, F>, Shift<S, F>, ClampMin<S, F>, Aggregate<S>, Thin<S, F>
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::{Exponential, UniformClosedOpen};
+    use crate::rngs::Xoshiro256PlusPlus;
+    use crate::series::Arma;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    fn mean<S: TimeSeries<f64>>(series: &mut S, n: usize) -> f64 {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        (0..n).map(|_| series.get_next(&mut rng)).sum::<f64>() / n as f64
+    }
+
+    #[test]
+    fn pointwise() {
+        let uniform = || FromDistribution::new(UniformClosedOpen::from(-1_f64..1.));
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+
+        // the same draws go through every combinator
+        let draws = (0..100)
+            .map(|_| rng.sample(&UniformClosedOpen::from(-1_f64..1.)))
+            .collect::<Vec<_>>();
+
+        let mut series = uniform().abs().scale(3.).shift(1.).clamp_min(2.);
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        for &x in &draws {
+            let y: f64 = series.get_next(&mut rng);
+            assert_eq!(y, (x.abs() * 3. + 1.).max(2.));
+        }
+
+        let mut series = uniform().map(|x: f64| x > 0.);
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        for &x in &draws {
+            assert_eq!(series.get_next(&mut rng), x > 0.);
+        }
+
+        let mut series = uniform().zip(uniform().scale(2.));
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        for pair in draws.chunks_exact(2) {
+            assert_eq!(series.get_next(&mut rng), (pair[0], 2. * pair[1]));
+        }
+
+        let mut series = uniform().aggregate(4);
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        for block in draws.chunks_exact(4) {
+            let sum: f64 = series.get_next(&mut rng);
+            assert_eq!(sum, block.iter().sum::<f64>());
+        }
+    }
+
+    #[test]
+    fn arrival_streams() {
+        let exponential = |mean: f64| FromDistribution::new(Exponential::new(mean));
+
+        // the superposition of Poisson processes is Poisson with the sum of
+        // the rates, and so is the thinning with p times the rate
+        let mut merged = exponential(1.).superpose(exponential(0.5));
+        assert!((mean(&mut merged, 200000) * 3. - 1.).abs() < 0.01);
+        let mut thinned = exponential(0.5).thin(0.25);
+        assert!((mean(&mut thinned, 200000) / 2. - 1.).abs() < 0.01);
+
+        // squared coefficient of variation 1 for a Poisson stream
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut merged = exponential(2.).superpose(exponential(2.).thin(0.5));
+        let samples = (0..200000)
+            .map(|_| merged.get_next(&mut rng))
+            .collect::<Vec<f64>>();
+        let m = samples.iter().sum::<f64>() / samples.len() as f64;
+        let v = samples.iter().map(|x| (x - m) * (x - m)).sum::<f64>() / samples.len() as f64;
+        assert!((m / (4. / 3.) - 1.).abs() < 0.01);
+        assert!((v / (m * m) - 1.).abs() < 0.02);
+    }
+
+    #[test]
+    fn state_goes_through() {
+        // the state of the ARMA and the residual times of the merge
+        let arma = Arma::new([0.7], [-0.3], 0.5, 0.).unwrap();
+        let mut merged = arma
+            .map(f64::exp)
+            .scale(2.)
+            .superpose(FromDistribution::new(Exponential::new(1.)));
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        for _ in 0..100 {
+            let _: f64 = merged.get_next(&mut rng);
+        }
+
+        let state = merged.state();
+        let mut fork_rng = rng.clone();
+        let path = (0..100)
+            .map(|_| merged.get_next(&mut rng))
+            .collect::<Vec<f64>>();
+        merged.set_state(state);
+        for &x in &path {
+            assert_eq!(merged.get_next(&mut fork_rng), x);
+        }
+
+        merged.reset();
+        assert_eq!(merged.state(), (([0.], [0.]), (), None));
+    }
+}
@@ -0,0 +1,577 @@
+use num::Float;
+
+use crate::distributions::{Distribution, Gamma, StandardNormal, StandardUniformOpenClosed};
+use crate::rngs::Rng;
+
+use super::{Stateful, TimeSeries};
+
+/// Continuous-time process sampled by its exact transition law, so that the
+/// time steps can be as large and as irregular as needed. Its state is its
+/// current value.
+pub trait Diffusion<F>: Stateful<State = F> {
+    // moves the process dt forward and returns its new value
+    fn advance<R>(&mut self, dt: F, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized;
+}
+
+/// dX = drift dt + volatility dW
+#[derive(Clone)]
+pub struct BrownianMotion<F> {
+    drift: F,
+    volatility: F,
+    value: F,
+    // the value `reset` returns to
+    initial: F,
+}
+
+impl<F> BrownianMotion<F>
+where
+    F: Float,
+{
+    pub fn new(drift: F, volatility: F, initial: F) -> Self {
+        assert!(
+            volatility >= F::ZERO,
+            "the volatility should be non-negative"
+        );
+        Self {
+            drift,
+            volatility,
+            value: initial,
+            initial,
+        }
+    }
+}
+
+impl<F> Diffusion<F> for BrownianMotion<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+{
+    fn advance<R>(&mut self, dt: F, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let z: F = rng.sample(&StandardNormal);
+        self.value += self.drift * dt + self.volatility * dt.sqrt() * z;
+        self.value
+    }
+}
+
+/// dX = rate (mean - X) dt + volatility dW, mean-reverting and Gaussian.
+#[derive(Clone)]
+pub struct OrnsteinUhlenbeck<F> {
+    rate: F,
+    mean: F,
+    volatility: F,
+    value: F,
+    // the value `reset` returns to
+    initial: F,
+}
+
+impl<F> OrnsteinUhlenbeck<F>
+where
+    F: Float,
+{
+    pub fn new(rate: F, mean: F, volatility: F, initial: F) -> Self {
+        assert!(rate > F::ZERO, "the rate should be positive");
+        assert!(
+            volatility >= F::ZERO,
+            "the volatility should be non-negative"
+        );
+        Self {
+            rate,
+            mean,
+            volatility,
+            value: initial,
+            initial,
+        }
+    }
+
+    // the stationary distribution is normal
+    pub fn stationary_mean(&self) -> F {
+        self.mean
+    }
+
+    pub fn stationary_variance(&self) -> F {
+        self.volatility * self.volatility / (F::from(2.) * self.rate)
+    }
+
+    // correlation of the stationary process between instants dt apart
+    pub fn autocorrelation(&self, dt: F) -> F {
+        (-self.rate * dt).exp()
+    }
+
+    pub fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+        StandardNormal: Distribution<F>,
+    {
+        let z: F = rng.sample(&StandardNormal);
+        self.value = self.mean + self.stationary_variance().sqrt() * z;
+    }
+}
+
+impl<F> Diffusion<F> for OrnsteinUhlenbeck<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+{
+    // X_{t+dt} ~ N(mean + (X_t - mean) e^(-rate dt),
+    //              volatility^2 (1 - e^(-2 rate dt)) / (2 rate))
+    fn advance<R>(&mut self, dt: F, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let decay = self.autocorrelation(dt);
+        let std_dev = (self.stationary_variance() * (F::ONE - decay * decay)).sqrt();
+        let z: F = rng.sample(&StandardNormal);
+        self.value = self.mean + (self.value - self.mean) * decay + std_dev * z;
+        self.value
+    }
+}
+
+/// dX = drift X dt + volatility X dW, log-normal and positive.
+#[derive(Clone)]
+pub struct GeometricBrownianMotion<F> {
+    drift: F,
+    volatility: F,
+    value: F,
+    // the value `reset` returns to
+    initial: F,
+}
+
+impl<F> GeometricBrownianMotion<F>
+where
+    F: Float,
+{
+    pub fn new(drift: F, volatility: F, initial: F) -> Self {
+        assert!(
+            volatility >= F::ZERO,
+            "the volatility should be non-negative"
+        );
+        assert!(initial > F::ZERO, "the initial value should be positive");
+        Self {
+            drift,
+            volatility,
+            value: initial,
+            initial,
+        }
+    }
+}
+
+impl<F> Diffusion<F> for GeometricBrownianMotion<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+{
+    // ln X is a Brownian motion with drift (drift - volatility^2 / 2)
+    fn advance<R>(&mut self, dt: F, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let z: F = rng.sample(&StandardNormal);
+        let mu = self.drift - F::from(0.5) * self.volatility * self.volatility;
+        self.value *= (mu * dt + self.volatility * dt.sqrt() * z).exp();
+        self.value
+    }
+}
+
+/// Cox-Ingersoll-Ross process, dX = rate (mean - X) dt + volatility sqrt(X) dW.
+/// It stays non-negative, and positive when 2 rate mean >= volatility^2.
+#[derive(Clone)]
+pub struct CoxIngersollRoss<F> {
+    rate: F,
+    mean: F,
+    volatility: F,
+    value: F,
+    // the value `reset` returns to
+    initial: F,
+}
+
+impl<F> CoxIngersollRoss<F>
+where
+    F: Float,
+{
+    pub fn new(rate: F, mean: F, volatility: F, initial: F) -> Self {
+        assert!(rate > F::ZERO, "the rate should be positive");
+        assert!(mean > F::ZERO, "the mean should be positive");
+        assert!(volatility > F::ZERO, "the volatility should be positive");
+        assert!(
+            initial >= F::ZERO,
+            "the initial value should be non-negative"
+        );
+        Self {
+            rate,
+            mean,
+            volatility,
+            value: initial,
+            initial,
+        }
+    }
+
+    // degrees of freedom of the non-central chi-square transitions
+    fn dof(&self) -> F {
+        F::from(4.) * self.rate * self.mean / (self.volatility * self.volatility)
+    }
+
+    // the stationary distribution is Gamma(dof / 2, volatility^2 / (2 rate))
+    pub fn stationary_mean(&self) -> F {
+        self.mean
+    }
+
+    pub fn stationary_variance(&self) -> F {
+        self.mean * self.volatility * self.volatility / (F::from(2.) * self.rate)
+    }
+
+    pub fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+        StandardNormal: Distribution<F>,
+        StandardUniformOpenClosed: Distribution<F>,
+    {
+        let scale = self.volatility * self.volatility / (F::from(2.) * self.rate);
+        self.value = rng.sample(&Gamma::new(F::from(0.5) * self.dof(), scale));
+    }
+}
+
+impl<F> Diffusion<F> for CoxIngersollRoss<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    // X_{t+dt} = c chi'^2(dof, lambda), non-central chi-square with
+    // c = volatility^2 (1 - e^(-rate dt)) / (4 rate) and
+    // lambda = X_t e^(-rate dt) / c
+    fn advance<R>(&mut self, dt: F, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        // c would be 0, e.g. for two events at the same instant
+        if dt == F::ZERO {
+            return self.value;
+        }
+
+        let decay = (-self.rate * dt).exp();
+        let c = self.volatility * self.volatility * (F::ONE - decay) / (F::from(4.) * self.rate);
+        let lambda = self.value * decay / c;
+        let dof = self.dof();
+
+        let chi2 = if dof > F::ONE {
+            // chi'^2(k, lambda) = (Z + sqrt(lambda))^2 + chi^2(k - 1)
+            let z: F = rng.sample(&StandardNormal);
+            let shifted = z + lambda.sqrt();
+            let central: F = rng.sample(&Gamma::new(F::from(0.5) * (dof - F::ONE), F::from(2.)));
+            shifted * shifted + central
+        } else {
+            // Poisson mixture of central chi-squares, chi^2(k + 2N) with
+            // N ~ Poisson(lambda / 2)
+            let n = poisson(F::from(0.5) * lambda, rng);
+            let shape = F::from(0.5) * dof + n;
+            rng.sample(&Gamma::new(shape, F::from(2.)))
+        };
+
+        self.value = c * chi2;
+        self.value
+    }
+}
+
+// Poisson variate, counted in F. Large means are reduced with the gamma
+// distributed arrival times of the underlying process (Ahrens and Dieter),
+// the rest by multiplying uniforms.
+pub(super) fn poisson<F, R>(mut mean: F, rng: &mut R) -> F
+where
+    F: Float,
+    R: Rng + ?Sized,
+    StandardNormal: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    let mut count = F::ZERO;
+    while mean > F::from(16.) {
+        // the m-th arrival falls at x
+        let m = (F::from(0.875) * mean).floor();
+        let x: F = rng.sample(&Gamma::new(m, F::ONE));
+        if x > mean {
+            // the first m - 1 arrivals are uniform on [0, x)
+            return count + binomial(m - F::ONE, mean / x, rng);
+        }
+        count += m;
+        mean -= x;
+    }
+
+    let limit = (-mean).exp();
+    let mut product: F = rng.sample(&StandardUniformOpenClosed);
+    while product > limit {
+        count += F::ONE;
+        let u: F = rng.sample(&StandardUniformOpenClosed);
+        product *= u;
+    }
+    count
+}
+
+// binomial variate, counted in F: the k-th smallest of n uniforms is
+// Beta(k, n + 1 - k), which halves n at each step
+fn binomial<F, R>(mut n: F, mut p: F, rng: &mut R) -> F
+where
+    F: Float,
+    R: Rng + ?Sized,
+    StandardNormal: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    let mut count = F::ZERO;
+    while n > F::from(16.) {
+        let k = ((n + F::ONE) * F::from(0.5)).floor();
+        let a: F = rng.sample(&Gamma::new(k, F::ONE));
+        let b: F = rng.sample(&Gamma::new(n + F::ONE - k, F::ONE));
+        let y = a / (a + b);
+        if y > p {
+            n = k - F::ONE;
+            p /= y;
+        } else {
+            count += k;
+            n -= k;
+            p = (p - y) / (F::ONE - y);
+        }
+    }
+
+    while n > F::ZERO {
+        let u: F = rng.sample(&StandardUniformOpenClosed);
+        if u <= p {
+            count += F::ONE;
+        }
+        n -= F::ONE;
+    }
+    count
+}
+
+macro_rules! stateful_impl {
+    ($($process:ident),*) => {
+        $(
+            impl<F> Stateful for $process<F>
+            where
+                F: Float,
+            {
+                type State = F;
+
+                fn state(&self) -> F {
+                    self.value
+                }
+
+                fn set_state(&mut self, value: F) {
+                    self.value = value;
+                }
+
+                fn reset(&mut self) {
+                    self.value = self.initial;
+                }
+            }
+        )*
+    };
+}
+
+stateful_impl! { BrownianMotion, OrnsteinUhlenbeck, GeometricBrownianMotion, CoxIngersollRoss }
+
+/// Samples of a diffusion on a regular grid, `step` apart.
+#[derive(Clone)]
+pub struct Grid<F, P> {
+    process: P,
+    step: F,
+}
+
+impl<F, P> Grid<F, P>
+where
+    F: Float,
+{
+    pub fn new(process: P, step: F) -> Self {
+        assert!(step > F::ZERO, "the step should be positive");
+        Self { process, step }
+    }
+
+    pub fn process(&self) -> &P {
+        &self.process
+    }
+
+    pub fn process_mut(&mut self) -> &mut P {
+        &mut self.process
+    }
+}
+
+impl<F, P> TimeSeries<F> for Grid<F, P>
+where
+    F: Float,
+    P: Diffusion<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        self.process.advance(self.step, rng)
+    }
+}
+
+impl<F, P> Stateful for Grid<F, P>
+where
+    P: Stateful,
+{
+    type State = P::State;
+
+    fn state(&self) -> P::State {
+        self.process.state()
+    }
+
+    fn set_state(&mut self, state: P::State) {
+        self.process.set_state(state);
+    }
+
+    fn reset(&mut self) {
+        self.process.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    fn mean_variance(data: &[f64]) -> (f64, f64) {
+        let n = data.len() as f64;
+        let mean = data.iter().sum::<f64>() / n;
+        let variance = data.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+        (mean, variance)
+    }
+
+    // lag-1 sample autocorrelation
+    fn autocorrelation(data: &[f64]) -> f64 {
+        let (mean, variance) = mean_variance(data);
+        let n = data.len() - 1;
+        let sum = (0..n)
+            .map(|i| (data[i] - mean) * (data[i + 1] - mean))
+            .sum::<f64>();
+        sum / n as f64 / variance
+    }
+
+    #[test]
+    fn irregular_steps() {
+        // the steps add up to 2 whatever their sizes
+        let steps = [0.5, 0.01, 0.99, 0.25, 0.125, 0.125];
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+
+        let mut bm = Vec::new();
+        let mut gbm = Vec::new();
+        for _ in 0..50000 {
+            let mut process = BrownianMotion::new(0.5, 2., 1.);
+            let mut geometric = GeometricBrownianMotion::new(0.1, 0.3, 2.);
+            for dt in steps {
+                process.advance(dt, &mut rng);
+                geometric.advance(dt, &mut rng);
+            }
+            bm.push(process.state());
+            gbm.push(geometric.state());
+        }
+
+        let (mean, variance) = mean_variance(&bm);
+        assert!((mean - 2.).abs() < 0.05);
+        assert!((variance / 8. - 1.).abs() < 0.03);
+
+        // E[X_t] = X_0 e^(drift t), E[ln X_t] = ln X_0 + (drift - sigma^2 / 2) t
+        let (mean, _) = mean_variance(&gbm);
+        assert!((mean / (2. * 0.2_f64.exp()) - 1.).abs() < 0.01);
+        let logs = gbm.iter().map(|x| x.ln()).collect::<Vec<_>>();
+        let (mean, variance) = mean_variance(&logs);
+        assert!((mean - 2_f64.ln() - 0.11).abs() < 0.01);
+        assert!((variance / 0.18 - 1.).abs() < 0.03);
+    }
+
+    #[test]
+    fn ornstein_uhlenbeck() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut process = OrnsteinUhlenbeck::new(2., 3., 1., 0.);
+        process.init_stationary(&mut rng);
+        let mut grid = Grid::new(process, 0.25);
+
+        let data = std::iter::repeat_with(|| rng.get_next(&mut grid))
+            .take(200000)
+            .collect::<Vec<f64>>();
+
+        let (mean, variance) = mean_variance(&data);
+        assert!((mean - 3.).abs() < 0.01);
+        assert!((variance / grid.process().stationary_variance() - 1.).abs() < 0.02);
+        let rho = grid.process().autocorrelation(0.25);
+        assert!((autocorrelation(&data) - rho).abs() < 0.01);
+    }
+
+    #[test]
+    fn cox_ingersoll_ross() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+
+        // 4 rate mean / volatility^2 = 6 and 0.5, the second one reaches 0
+        for (volatility, step) in [(0.5, 0.1), (2., 0.01)] {
+            let mut process = CoxIngersollRoss::new(1.5, 0.25, volatility, 1.);
+            process.init_stationary(&mut rng);
+            let mut grid = Grid::new(process, step);
+
+            let data = std::iter::repeat_with(|| rng.get_next(&mut grid))
+                .take(500000)
+                .collect::<Vec<f64>>();
+            assert!(data.iter().all(|&x| x >= 0.));
+
+            let (mean, variance) = mean_variance(&data);
+            let expected = grid.process().stationary_variance();
+            assert!((mean / 0.25 - 1.).abs() < 0.05, "{mean}");
+            assert!((variance / expected - 1.).abs() < 0.1, "{variance}");
+        }
+    }
+
+    #[test]
+    fn zero_steps() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut bm = BrownianMotion::new(0.5, 2., 1.);
+        let mut ou = OrnsteinUhlenbeck::new(2., 3., 1., 0.5);
+        let mut gbm = GeometricBrownianMotion::new(0.1, 0.3, 2.);
+        let mut cir = CoxIngersollRoss::new(1.5, 0.25, 0.5, 1.);
+
+        for dt in [0.25, 0., 0., 0.5] {
+            let values = [bm.state(), ou.state(), gbm.state(), cir.state()];
+            let advanced = [
+                bm.advance(dt, &mut rng),
+                ou.advance(dt, &mut rng),
+                gbm.advance(dt, &mut rng),
+                cir.advance(dt, &mut rng),
+            ];
+            assert!(advanced.iter().all(|x: &f64| x.is_finite()));
+            if dt == 0. {
+                assert_eq!(advanced, values);
+            }
+        }
+    }
+
+    #[test]
+    fn poisson_and_binomial() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+
+        for mean in [0.5, 10., 300.] {
+            let data = std::iter::repeat_with(|| poisson(mean, &mut rng))
+                .take(100000)
+                .collect::<Vec<f64>>();
+            assert!(data.iter().all(|&x| x >= 0. && x.fract() == 0.));
+            let (m, v) = mean_variance(&data);
+            assert!((m / mean - 1.).abs() < 0.01);
+            assert!((v / mean - 1.).abs() < 0.03);
+        }
+
+        let data = std::iter::repeat_with(|| binomial(1000., 0.3, &mut rng))
+            .take(100000)
+            .collect::<Vec<f64>>();
+        let (m, v) = mean_variance(&data);
+        assert!((m / 300. - 1.).abs() < 0.01);
+        assert!((v / 210. - 1.).abs() < 0.03);
+    }
+}
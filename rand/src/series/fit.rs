@@ -0,0 +1,641 @@
+use num::linalg::{levinson, Matrix};
+use num::optimize::nelder_mead;
+use num::poly::roots_outside_unit_circle;
+use num::special::gamma_q;
+use num::Float;
+
+use crate::distributions::Normal;
+use crate::fit::FitError;
+
+use super::{ArimaModel, Arma};
+
+const MAX_ITERATIONS: usize = 2000;
+
+pub struct ArmaFit<F, const P: usize, const Q: usize> {
+    pub model: Arma<F, P, Q>,
+    // exact Gaussian log-likelihood of the fitted model
+    pub log_likelihood: F,
+    pub aic: F,
+    pub bic: F,
+    // one-step prediction errors of the fitted model, scaled to the
+    // innovation variance
+    pub residuals: Vec<F>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    Aic,
+    Bic,
+}
+
+pub struct OrderSelection<F> {
+    pub p: usize,
+    pub q: usize,
+    // (p, q, aic, bic) of every candidate that could be fitted
+    pub candidates: Vec<(usize, usize, F, F)>,
+    // the selected model, fitted by conditional maximum likelihood
+    pub model: ArimaModel<F, Normal<F>>,
+}
+
+pub struct LjungBox<F> {
+    pub statistic: F,
+    pub degrees_of_freedom: usize,
+    // probability of a larger statistic if the residuals are white noise
+    pub p_value: F,
+}
+
+fn mean<F>(data: &[F]) -> F
+where
+    F: Float,
+{
+    let mut sum = F::ZERO;
+    for &x in data {
+        sum += x;
+    }
+    sum / F::from(data.len() as f64)
+}
+
+// biased sample autocovariances of lags 0 to max_lag, divided by n
+pub fn sample_autocovariance<F>(data: &[F], max_lag: usize) -> Vec<F>
+where
+    F: Float,
+{
+    let mean = mean(data);
+    let n = F::from(data.len() as f64);
+
+    (0..=max_lag)
+        .map(|k| {
+            let mut sum = F::ZERO;
+            for (&x, &y) in data.iter().zip(data.iter().skip(k)) {
+                sum += (x - mean) * (y - mean);
+            }
+            sum / n
+        })
+        .collect()
+}
+
+// e_t = y_t - sum phi_i y_{t-i} - sum theta_j e_{t-j}, with the values
+// before the start of the series taken as zero
+fn conditional_residuals<F>(y: &[F], phi: &[F], theta: &[F]) -> Vec<F>
+where
+    F: Float,
+{
+    let mut e: Vec<F> = Vec::with_capacity(y.len());
+    for t in 0..y.len() {
+        let mut r = y[t];
+        for (i, &phi) in phi.iter().enumerate().take(t) {
+            r -= phi * y[t - i - 1];
+        }
+        for (j, &theta) in theta.iter().enumerate().take(t) {
+            r -= theta * e[t - j - 1];
+        }
+        e.push(r);
+    }
+    e
+}
+
+struct Kalman<F> {
+    log_likelihood: F,
+    variance: F,
+    residuals: Vec<F>,
+}
+
+// exact Gaussian likelihood of the zero-mean series `y` by the Kalman filter
+// on the state space form with state dimension r = max(p, q + 1)
+//   a_{t+1} = T a_t + R e_{t+1},  y_t = a_t[0]
+// where T has phi in the first column and ones on the superdiagonal, and
+// R = (1, theta_1, ..., theta_{r-1}). the innovation variance is
+// concentrated out of the likelihood
+fn kalman<F>(y: &[F], phi: &[F], theta: &[F]) -> Option<Kalman<F>>
+where
+    F: Float,
+{
+    let r = phi.len().max(theta.len() + 1);
+    let t_col = |i: usize| phi.get(i).copied().unwrap_or(F::ZERO);
+    let rr = |i: usize| match i {
+        0 => F::ONE,
+        i => theta.get(i - 1).copied().unwrap_or(F::ZERO),
+    };
+
+    // stationary state covariance, P = T P T' + R R'
+    let mut a = Matrix::identity(r * r);
+    for i in 0..r {
+        for j in 0..r {
+            for k in 0..r {
+                for l in 0..r {
+                    let tik = if k == 0 { t_col(i) } else { one_if(k == i + 1) };
+                    let tjl = if l == 0 { t_col(j) } else { one_if(l == j + 1) };
+                    a[(i * r + j, k * r + l)] -= tik * tjl;
+                }
+            }
+        }
+    }
+    let b = (0..r * r)
+        .map(|ij| rr(ij / r) * rr(ij % r))
+        .collect::<Vec<_>>();
+    let p0 = a.solve(&b).ok()?;
+
+    let mut p = Matrix::zeros(r, r);
+    for i in 0..r {
+        for j in 0..r {
+            p[(i, j)] = p0[i * r + j];
+        }
+    }
+    let mut state = vec![F::ZERO; r];
+
+    let mut sum_ln_f = F::ZERO;
+    let mut sum_v2 = F::ZERO;
+    let mut residuals = Vec::with_capacity(y.len());
+    let mut m = Matrix::zeros(r, r);
+
+    for &y in y {
+        let f = p[(0, 0)];
+        if f <= F::ZERO {
+            return None;
+        }
+        let v = y - state[0];
+        sum_ln_f += f.ln();
+        sum_v2 += v * v / f;
+        residuals.push(v / f.sqrt());
+
+        // filtering, a = a + P[:, 0] v / f, P = P - P[:, 0] P[0, :] / f
+        for i in 0..r {
+            state[i] += p[(i, 0)] * v / f;
+        }
+        for i in 0..r {
+            for j in 0..r {
+                m[(i, j)] = p[(i, j)] - p[(i, 0)] * p[(0, j)] / f;
+            }
+        }
+
+        // prediction, a = T a, P = T P T' + R R'
+        let first = state[0];
+        for i in 0..r {
+            let next = if i + 1 < r { state[i + 1] } else { F::ZERO };
+            state[i] = t_col(i) * first + next;
+        }
+        for i in 0..r {
+            for j in 0..r {
+                let next = if i + 1 < r { m[(i + 1, j)] } else { F::ZERO };
+                p[(i, j)] = t_col(i) * m[(0, j)] + next;
+            }
+        }
+        for i in 0..r {
+            for j in 0..r {
+                m[(i, j)] = p[(i, j)];
+            }
+        }
+        for i in 0..r {
+            for j in 0..r {
+                let next = if j + 1 < r { m[(i, j + 1)] } else { F::ZERO };
+                p[(i, j)] = t_col(j) * m[(i, 0)] + next + rr(i) * rr(j);
+            }
+        }
+    }
+
+    let n = F::from(y.len() as f64);
+    let variance = sum_v2 / n;
+    let log_likelihood = -n / F::from(2.) * ((F::from(2.) * F::PI).ln() + F::ONE + variance.ln())
+        - sum_ln_f / F::from(2.);
+
+    let scale = variance.sqrt();
+    for r in &mut residuals {
+        *r *= scale;
+    }
+
+    Some(Kalman {
+        log_likelihood,
+        variance,
+        residuals,
+    })
+}
+
+fn one_if<F>(condition: bool) -> F
+where
+    F: Float,
+{
+    if condition {
+        F::ONE
+    } else {
+        F::ZERO
+    }
+}
+
+fn is_valid<F>(phi: &[F], theta: &[F]) -> bool
+where
+    F: Float,
+{
+    let ar = phi.iter().map(|&phi| -phi).collect::<Vec<_>>();
+    roots_outside_unit_circle(&ar) && roots_outside_unit_circle(theta)
+}
+
+// Yule-Walker estimates of an AR(p), by the Levinson-Durbin recursion
+fn yule_walker_coefficients<F>(data: &[F], p: usize) -> (Vec<F>, F)
+where
+    F: Float,
+{
+    let fit = levinson(&sample_autocovariance(data, p));
+    (fit.coefficients, fit.variance)
+}
+
+// Hannan-Rissanen: a long autoregression estimates the innovations, then
+// x_t is regressed on x_{t-1..t-p} and the estimated e_{t-1..t-q}
+fn hannan_rissanen_coefficients<F>(y: &[F], p: usize, q: usize) -> Option<(Vec<F>, Vec<F>)>
+where
+    F: Float,
+{
+    if q == 0 {
+        return Some((yule_walker_coefficients(y, p).0, Vec::new()));
+    }
+
+    // order of the long autoregression, 10 log10(n) as in R's ar()
+    let n = y.len();
+    let m = (p + q + 1).max(10 * n.max(1).ilog10() as usize).min(n / 4);
+    if m < p + q + 1 {
+        return None;
+    }
+
+    let (a, _) = yule_walker_coefficients(y, m);
+    let mut e = vec![F::ZERO; n];
+    for t in m..n {
+        let mut r = y[t];
+        for (i, &a) in a.iter().enumerate() {
+            r -= a * y[t - i - 1];
+        }
+        e[t] = r;
+    }
+
+    // normal equations of the least squares regression
+    let k = p + q;
+    let start = m + q;
+    let mut xtx = Matrix::zeros(k, k);
+    let mut xty = vec![F::ZERO; k];
+    let mut row = vec![F::ZERO; k];
+    for t in start..n {
+        for i in 0..p {
+            row[i] = y[t - i - 1];
+        }
+        for j in 0..q {
+            row[p + j] = e[t - j - 1];
+        }
+        for i in 0..k {
+            xty[i] += row[i] * y[t];
+            for j in 0..k {
+                xtx[(i, j)] += row[i] * row[j];
+            }
+        }
+    }
+    let beta = xtx.solve(&xty).ok()?;
+
+    Some((beta[..p].to_vec(), beta[p..].to_vec()))
+}
+
+fn to_array<F, const N: usize>(v: &[F]) -> [F; N]
+where
+    F: Float,
+{
+    core::array::from_fn(|i| v[i])
+}
+
+// finishes a fit from the estimated coefficients, the mean is the sample mean
+fn finish<F, const P: usize, const Q: usize>(
+    data: &[F],
+    phi: &[F],
+    theta: &[F],
+) -> Result<ArmaFit<F, P, Q>, FitError>
+where
+    F: Float,
+{
+    if !is_valid(phi, theta) {
+        return Err(FitError::InvalidEstimate);
+    }
+
+    let mean = mean(data);
+    let y = data.iter().map(|&x| x - mean).collect::<Vec<_>>();
+    let kalman = kalman(&y, phi, theta).ok_or(FitError::InvalidEstimate)?;
+
+    let model = Arma::new(to_array(phi), to_array(theta), kalman.variance.sqrt(), mean)
+        .map_err(|_| FitError::InvalidEstimate)?;
+
+    let (aic, bic) = criteria(kalman.log_likelihood, P + Q, data.len());
+    Ok(ArmaFit {
+        model,
+        log_likelihood: kalman.log_likelihood,
+        aic,
+        bic,
+        residuals: kalman.residuals,
+    })
+}
+
+// the parameters are the coefficients plus the mean and the variance
+fn criteria<F>(log_likelihood: F, coefficients: usize, n: usize) -> (F, F)
+where
+    F: Float,
+{
+    let k = F::from((coefficients + 2) as f64);
+    let n = F::from(n as f64);
+    let aic = F::from(-2.) * log_likelihood + F::from(2.) * k;
+    let bic = F::from(-2.) * log_likelihood + k * n.ln();
+    (aic, bic)
+}
+
+fn check<F>(data: &[F], coefficients: usize) -> Result<(), FitError>
+where
+    F: Float,
+{
+    let minimum = 2 * (coefficients + 2);
+    if data.len() < minimum {
+        return Err(FitError::NotEnoughData { minimum });
+    }
+    Ok(())
+}
+
+pub fn yule_walker<F, const P: usize>(data: &[F]) -> Result<ArmaFit<F, P, 0>, FitError>
+where
+    F: Float,
+{
+    check(data, P)?;
+    let (phi, _) = yule_walker_coefficients(data, P);
+    finish(data, &phi, &[])
+}
+
+pub fn hannan_rissanen<F, const P: usize, const Q: usize>(
+    data: &[F],
+) -> Result<ArmaFit<F, P, Q>, FitError>
+where
+    F: Float,
+{
+    check(data, P + Q)?;
+    let mean = mean(data);
+    let y = data.iter().map(|&x| x - mean).collect::<Vec<_>>();
+    let (phi, theta) = hannan_rissanen_coefficients(&y, P, Q).ok_or(FitError::NoConvergence)?;
+    finish(data, &phi, &theta)
+}
+
+// maximizes the likelihood conditional on the pre-sample values being zero,
+// i.e. minimizes the sum of the squared conditional residuals
+fn conditional_coefficients<F>(y: &[F], p: usize, q: usize) -> Result<(Vec<F>, Vec<F>), FitError>
+where
+    F: Float,
+{
+    let (phi0, theta0) = hannan_rissanen_coefficients(y, p, q)
+        .filter(|(phi, theta)| is_valid(phi, theta))
+        .unwrap_or((vec![F::ZERO; p], vec![F::ZERO; q]));
+    let mut x0 = phi0;
+    x0.extend(theta0);
+
+    let objective = |x: &[F]| {
+        let (phi, theta) = x.split_at(p);
+        if !is_valid(phi, theta) {
+            return F::INFINITY;
+        }
+        let e = conditional_residuals(y, phi, theta);
+        let mut sum = F::ZERO;
+        for &e in &e[p..] {
+            sum += e * e;
+        }
+        sum
+    };
+
+    let tol = F::EPSILON.sqrt();
+    let x = nelder_mead(objective, &x0, F::from(0.1), tol, MAX_ITERATIONS);
+    if objective(&x) == F::INFINITY {
+        return Err(FitError::NoConvergence);
+    }
+    let (phi, theta) = x.split_at(p);
+    Ok((phi.to_vec(), theta.to_vec()))
+}
+
+pub fn conditional_mle<F, const P: usize, const Q: usize>(
+    data: &[F],
+) -> Result<ArmaFit<F, P, Q>, FitError>
+where
+    F: Float,
+{
+    check(data, P + Q)?;
+    let mean = mean(data);
+    let y = data.iter().map(|&x| x - mean).collect::<Vec<_>>();
+    let (phi, theta) = conditional_coefficients(&y, P, Q)?;
+    finish(data, &phi, &theta)
+}
+
+// maximizes the exact Gaussian likelihood (Kalman filter), starting from the
+// conditional estimates
+pub fn exact_mle<F, const P: usize, const Q: usize>(
+    data: &[F],
+) -> Result<ArmaFit<F, P, Q>, FitError>
+where
+    F: Float,
+{
+    check(data, P + Q)?;
+    let mean = mean(data);
+    let y = data.iter().map(|&x| x - mean).collect::<Vec<_>>();
+    let (phi0, theta0) = conditional_coefficients(&y, P, Q)?;
+    let mut x0 = phi0;
+    x0.extend(theta0);
+
+    let objective = |x: &[F]| {
+        let (phi, theta) = x.split_at(P);
+        if !is_valid(phi, theta) {
+            return F::INFINITY;
+        }
+        match kalman(&y, phi, theta) {
+            Some(kalman) => -kalman.log_likelihood,
+            None => F::INFINITY,
+        }
+    };
+
+    let tol = F::EPSILON.sqrt();
+    let x = nelder_mead(objective, &x0, F::from(0.05), tol, MAX_ITERATIONS);
+    let (phi, theta) = x.split_at(P);
+    finish(data, phi, theta)
+}
+
+// fits every ARMA(p, q) with p <= max_p and q <= max_q by conditional
+// maximum likelihood and picks the one with the smallest criterion
+pub fn select_order<F>(
+    data: &[F],
+    max_p: usize,
+    max_q: usize,
+    criterion: Criterion,
+) -> Result<OrderSelection<F>, FitError>
+where
+    F: Float,
+{
+    check(data, max_p + max_q)?;
+    let mean = mean(data);
+    let y = data.iter().map(|&x| x - mean).collect::<Vec<_>>();
+
+    let mut candidates = Vec::new();
+    let mut best: Option<(F, Vec<F>, Vec<F>, F)> = None;
+    for p in 0..=max_p {
+        for q in 0..=max_q {
+            let Ok((phi, theta)) = conditional_coefficients(&y, p, q) else {
+                continue;
+            };
+            let Some(kalman) = kalman(&y, &phi, &theta) else {
+                continue;
+            };
+
+            let (aic, bic) = criteria(kalman.log_likelihood, p + q, data.len());
+            candidates.push((p, q, aic, bic));
+
+            let value = match criterion {
+                Criterion::Aic => aic,
+                Criterion::Bic => bic,
+            };
+            if best.as_ref().map_or(true, |(v, ..)| value < *v) {
+                best = Some((value, phi, theta, kalman.variance));
+            }
+        }
+    }
+
+    let (_, phi, theta, variance) = best.ok_or(FitError::NoConvergence)?;
+    let (p, q) = (phi.len(), theta.len());
+    let innovation = Normal::new(F::ZERO, variance.sqrt());
+    let model = ArimaModel::new(phi, 0, theta, None, mean, innovation)
+        .map_err(|_| FitError::InvalidEstimate)?;
+
+    Ok(OrderSelection {
+        p,
+        q,
+        candidates,
+        model,
+    })
+}
+
+// Ljung-Box portmanteau test on the first `lags` autocorrelations of the
+// residuals, `fitted` is the number of estimated ARMA coefficients (p + q)
+pub fn ljung_box<F>(residuals: &[F], lags: usize, fitted: usize) -> LjungBox<F>
+where
+    F: Float,
+{
+    assert!(
+        lags > fitted,
+        "there should be more lags than fitted coefficients"
+    );
+
+    let gamma = sample_autocovariance(residuals, lags);
+    let n = residuals.len();
+    let nf = F::from(n as f64);
+
+    let mut sum = F::ZERO;
+    for (k, &g) in gamma.iter().enumerate().skip(1) {
+        let rho = g / gamma[0];
+        sum += rho * rho / F::from((n - k) as f64);
+    }
+    let statistic = nf * (nf + F::from(2.)) * sum;
+
+    // chi-square survival function
+    let degrees_of_freedom = lags - fitted;
+    let p_value = gamma_q(
+        F::from(degrees_of_freedom as f64) / F::from(2.),
+        statistic / F::from(2.),
+    );
+
+    LjungBox {
+        statistic,
+        degrees_of_freedom,
+        p_value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::{Rng, Xoshiro256PlusPlus};
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    fn simulate<const P: usize, const Q: usize>(arma: Arma<f64, P, Q>, count: usize) -> Vec<f64> {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut arma = arma;
+        arma.init_stationary(&mut rng).unwrap();
+        std::iter::repeat_with(|| rng.get_next(&mut arma))
+            .take(count)
+            .collect()
+    }
+
+    fn assert_close(x: f64, y: f64, tol: f64) {
+        assert!((x - y).abs() < tol, "{x} != {y}");
+    }
+
+    #[test]
+    fn yule_walker_works() {
+        let data = simulate(Arma::new([0.5, 0.3], [], 2., 1.).unwrap(), 20000);
+        let fit = yule_walker::<_, 2>(&data).unwrap();
+
+        assert_close(fit.model.autocorrelation(1)[1], 0.5 / 0.7, 0.02);
+        assert_close(fit.model.partial_autocorrelation(2)[1], 0.3, 0.02);
+        assert_close(fit.model.mean(), 1., 0.2);
+        assert!(fit.log_likelihood.is_finite());
+
+        // two samples per parameter, the mean and the variance included
+        assert_eq!(
+            yule_walker::<_, 2>(&data[..7]).err(),
+            Some(FitError::NotEnoughData { minimum: 8 })
+        );
+    }
+
+    #[test]
+    fn arma_estimators_work() {
+        let (phi, theta, variance) = (0.7, -0.3, 3.);
+        let data = simulate(
+            Arma::new([phi], [theta], variance.sqrt(), 5.).unwrap(),
+            20000,
+        );
+
+        let check = |fit: ArmaFit<f64, 1, 1>| {
+            let truth = Arma::new([phi], [theta], variance.sqrt(), 5.).unwrap();
+            for (a, b) in fit
+                .model
+                .autocovariance(3)
+                .iter()
+                .zip(truth.autocovariance(3))
+            {
+                assert_close(*a, b, 0.3);
+            }
+            assert_close(
+                fit.model.spectral_density(0.),
+                truth.spectral_density(0.),
+                0.3,
+            );
+            fit.log_likelihood
+        };
+
+        let hr = check(hannan_rissanen(&data).unwrap());
+        let css = check(conditional_mle(&data).unwrap());
+        let exact = check(exact_mle(&data).unwrap());
+
+        // the exact estimate maximizes the exact likelihood
+        assert!(exact >= css - 1e-6 && exact >= hr - 1e-6);
+    }
+
+    #[test]
+    fn selects_order() {
+        let data = simulate(Arma::new([0.7], [-0.3], 3_f64.sqrt(), 5.).unwrap(), 5000);
+        let selection = select_order(&data, 2, 2, Criterion::Bic).unwrap();
+
+        assert_eq!((selection.p, selection.q), (1, 1));
+        assert_eq!(selection.candidates.len(), 9);
+        assert_eq!(selection.model.ar().len(), 1);
+    }
+
+    #[test]
+    fn ljung_box_detects_autocorrelation() {
+        let data = simulate(Arma::new([0.7], [-0.3], 1., 0.).unwrap(), 5000);
+
+        let good = hannan_rissanen::<_, 1, 1>(&data).unwrap();
+        let test = ljung_box(&good.residuals, 20, 2);
+        assert_eq!(test.degrees_of_freedom, 18);
+        assert!(test.p_value > 0.01);
+
+        let bad = yule_walker::<_, 0>(&data).unwrap();
+        assert!(ljung_box(&bad.residuals, 20, 0).p_value < 1e-6);
+    }
+}
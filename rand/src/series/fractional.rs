@@ -0,0 +1,552 @@
+use num::complex::Complex;
+use num::fft::fft;
+use num::special::ln_gamma;
+use num::Float;
+
+use crate::distributions::{Distribution, StandardNormal};
+use crate::rngs::Rng;
+
+use super::{burn_in, Arma, ArmaError, Stateful, TimeSeries};
+
+// stationary Gaussian series with autocovariance gamma, generated in
+// independent blocks of `block_len` samples by circulant embedding
+// (Davies-Harte). The covariance is exact within a block, the blocks are
+// independent of each other.
+#[derive(Clone)]
+struct CirculantEmbedding<F> {
+    // sqrt(lambda_k / 2m) for the eigenvalues lambda of the circulant matrix
+    scale: Vec<F>,
+    block_len: usize,
+    block: Vec<F>,
+    // a second block comes for free with the imaginary part
+    spare: Vec<F>,
+    position: usize,
+}
+
+impl<F> CirculantEmbedding<F>
+where
+    F: Float,
+{
+    // None if the circulant matrix is not non-negative definite
+    fn new(gamma: impl Fn(usize) -> F, block_len: usize) -> Option<Self> {
+        assert!(block_len > 0, "the block length should be at least 1");
+
+        // first row (gamma(0), ..., gamma(m), gamma(m - 1), ..., gamma(1))
+        let m = (block_len - 1).max(1).next_power_of_two();
+        let mut c = (0..2 * m)
+            .map(|j| Complex::from_real(gamma(j.min(2 * m - j))))
+            .collect::<Vec<_>>();
+        fft(&mut c);
+
+        // the eigenvalues are real, small negative ones are rounding errors
+        let max = c.iter().fold(F::ZERO, |max, lambda| max.max(lambda.re));
+        let tolerance = F::from(1e-10) * max;
+        if c.iter().any(|lambda| lambda.re < -tolerance) {
+            return None;
+        }
+
+        let n = F::from((2 * m) as f64);
+        let scale = c
+            .iter()
+            .map(|lambda| (lambda.re.max(F::ZERO) / n).sqrt())
+            .collect();
+
+        Some(Self {
+            scale,
+            block_len,
+            block: Vec::with_capacity(block_len),
+            spare: Vec::with_capacity(block_len),
+            position: 0,
+        })
+    }
+
+    // with W = F diag(sqrt(lambda / 2m)) (Z_1 + i Z_2), Re W and Im W are
+    // independent with the circulant covariance
+    fn fill<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+        StandardNormal: Distribution<F>,
+    {
+        let mut w = self
+            .scale
+            .iter()
+            .map(|&s| {
+                Complex::new(
+                    s * rng.sample(&StandardNormal),
+                    s * rng.sample(&StandardNormal),
+                )
+            })
+            .collect::<Vec<_>>();
+        fft(&mut w);
+
+        self.block.clear();
+        self.block.extend(w[..self.block_len].iter().map(|w| w.re));
+        self.spare.clear();
+        self.spare.extend(w[..self.block_len].iter().map(|w| w.im));
+    }
+}
+
+impl<F> TimeSeries<F> for CirculantEmbedding<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        if self.position == self.block.len() {
+            if self.spare.is_empty() {
+                self.fill(rng);
+            } else {
+                core::mem::swap(&mut self.block, &mut self.spare);
+                self.spare.clear();
+            }
+            self.position = 0;
+        }
+
+        let x = self.block[self.position];
+        self.position += 1;
+        x
+    }
+}
+
+// the samples left in the current block, the spare block and the position
+// in the current one
+impl<F> Stateful for CirculantEmbedding<F>
+where
+    F: Float,
+{
+    type State = (Vec<F>, Vec<F>, usize);
+
+    fn state(&self) -> Self::State {
+        (self.block.clone(), self.spare.clone(), self.position)
+    }
+
+    fn set_state(&mut self, (block, spare, position): Self::State) {
+        assert!(
+            position <= block.len(),
+            "the position should be in the block"
+        );
+        self.block = block;
+        self.spare = spare;
+        self.position = position;
+    }
+
+    fn reset(&mut self) {
+        self.block.clear();
+        self.spare.clear();
+        self.position = 0;
+    }
+}
+
+// exact sample of a stationary Gaussian series with autocovariance
+// gamma(0..n), drawing each x_t from its conditional distribution given the
+// past by the Durbin-Levinson recursion, O(n^2)
+fn hosking<F, R>(gamma: &[F], rng: &mut R) -> Vec<F>
+where
+    F: Float,
+    R: Rng + ?Sized,
+    StandardNormal: Distribution<F>,
+{
+    let n = gamma.len();
+    let mut x = Vec::with_capacity(n);
+    if n == 0 {
+        return x;
+    }
+
+    let mut phi: Vec<F> = Vec::with_capacity(n);
+    let mut variance = gamma[0];
+    x.push(variance.sqrt() * rng.sample(&StandardNormal));
+
+    for t in 1..n {
+        let mut k = gamma[t];
+        for (j, &phi) in phi.iter().enumerate() {
+            k -= phi * gamma[t - 1 - j];
+        }
+        let k = k / variance;
+
+        let previous = phi.clone();
+        for (j, phi) in phi.iter_mut().enumerate() {
+            *phi -= k * previous[t - 2 - j];
+        }
+        phi.push(k);
+        variance *= F::ONE - k * k;
+
+        let mut mean = F::ZERO;
+        for (j, &phi) in phi.iter().enumerate() {
+            mean += phi * x[t - 1 - j];
+        }
+        x.push(mean + variance.sqrt() * rng.sample(&StandardNormal));
+    }
+
+    x
+}
+
+/// Fractional Gaussian noise, the increments of a fractional Brownian motion
+/// with Hurst exponent `H` in (0, 1). The autocorrelation decays as
+/// `H (2H - 1) k^(2H - 2)`, so the series is long-range dependent for
+/// `H > 1/2`, white noise for `H = 1/2` and anti-persistent below.
+///
+/// As a `TimeSeries` it is generated exactly in independent blocks of
+/// `block_len` samples, which should be longer than the horizon of interest.
+#[derive(Clone)]
+pub struct FractionalGaussianNoise<F> {
+    hurst: F,
+    std_dev: F,
+    embedding: CirculantEmbedding<F>,
+}
+
+impl<F> FractionalGaussianNoise<F>
+where
+    F: Float,
+{
+    pub fn new(hurst: F, std_dev: F, block_len: usize) -> Self {
+        assert!(
+            hurst > F::ZERO && hurst < F::ONE,
+            "the Hurst exponent should be in (0, 1)"
+        );
+
+        let variance = std_dev * std_dev;
+        let embedding =
+            CirculantEmbedding::new(|k| fgn_autocovariance(hurst, variance, k), block_len).expect(
+                "the circulant embedding of fractional Gaussian noise is non-negative definite",
+            );
+
+        Self {
+            hurst,
+            std_dev,
+            embedding,
+        }
+    }
+
+    pub fn hurst(&self) -> F {
+        self.hurst
+    }
+
+    pub fn std_dev(&self) -> F {
+        self.std_dev
+    }
+
+    // gamma(0..=max_lag)
+    pub fn autocovariance(&self, max_lag: usize) -> Vec<F> {
+        let variance = self.std_dev * self.std_dev;
+        (0..=max_lag)
+            .map(|k| fgn_autocovariance(self.hurst, variance, k))
+            .collect()
+    }
+
+    // exact sample path by the Hosking method, O(n^2) but without the block
+    // structure, for short series
+    pub fn hosking<R>(&self, n: usize, rng: &mut R) -> Vec<F>
+    where
+        R: Rng + ?Sized,
+        StandardNormal: Distribution<F>,
+    {
+        let gamma = (0..n).map(|k| fgn_autocovariance(self.hurst, self.std_dev * self.std_dev, k));
+        hosking(&gamma.collect::<Vec<_>>(), rng)
+    }
+}
+
+// sigma^2 / 2 (|k + 1|^2H - 2 |k|^2H + |k - 1|^2H)
+fn fgn_autocovariance<F>(hurst: F, variance: F, k: usize) -> F
+where
+    F: Float,
+{
+    let two_h = F::from(2.) * hurst;
+    let k = F::from(k as f64);
+    let power = |x: F| x.abs().powf(two_h);
+    F::from(0.5) * variance * (power(k + F::ONE) - F::from(2.) * power(k) + power(k - F::ONE))
+}
+
+impl<F> TimeSeries<F> for FractionalGaussianNoise<F>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        self.embedding.get_next(rng)
+    }
+}
+
+impl<F> Stateful for FractionalGaussianNoise<F>
+where
+    F: Float,
+{
+    type State = (Vec<F>, Vec<F>, usize);
+
+    fn state(&self) -> Self::State {
+        self.embedding.state()
+    }
+
+    fn set_state(&mut self, state: Self::State) {
+        self.embedding.set_state(state);
+    }
+
+    fn reset(&mut self) {
+        self.embedding.reset();
+    }
+}
+
+/// FARIMA(P, d, Q), an ARMA(P, Q) driven by fractionally integrated noise
+/// `(1 - B)^(-d) e_t`, with `d = H - 1/2` for the Hurst exponent `H`.
+///
+/// The fractional noise is generated exactly in independent blocks as for
+/// `FractionalGaussianNoise`, the ARMA filter starts from a zero state until
+/// `init_stationary` is called.
+#[derive(Clone)]
+pub struct Farima<F, const P: usize, const Q: usize> {
+    arma: Arma<F, P, Q, CirculantEmbedding<F>>,
+    hurst: F,
+    std_dev: F,
+}
+
+impl<F, const P: usize, const Q: usize> Farima<F, P, Q>
+where
+    F: Float,
+{
+    // std_dev is the standard deviation of the white noise e_t
+    pub fn new(
+        phi: [F; P],
+        theta: [F; Q],
+        hurst: F,
+        std_dev: F,
+        mean: F,
+        block_len: usize,
+    ) -> Result<Self, ArmaError<F>> {
+        assert!(
+            hurst > F::ZERO && hurst < F::ONE,
+            "the Hurst exponent should be in (0, 1)"
+        );
+
+        let d = hurst - F::from(0.5);
+        // the embedding needs lags up to the next power of two
+        let gamma = fractional_autocovariance(d, std_dev * std_dev, 2 * block_len);
+        let embedding = CirculantEmbedding::new(|k| gamma[k], block_len)
+            .expect("the circulant embedding of fractional noise is non-negative definite");
+
+        let arma = Arma::with_innovation(phi, theta, embedding, mean)?;
+        Ok(Self {
+            arma,
+            hurst,
+            std_dev,
+        })
+    }
+
+    pub fn hurst(&self) -> F {
+        self.hurst
+    }
+
+    pub fn d(&self) -> F {
+        self.hurst - F::from(0.5)
+    }
+
+    // autocovariance gamma(0..=max_lag) of the fractional noise alone,
+    // i.e. of FARIMA(0, d, 0)
+    pub fn fractional_autocovariance(&self, max_lag: usize) -> Vec<F> {
+        fractional_autocovariance(self.d(), self.std_dev * self.std_dev, max_lag)
+    }
+
+    // runs the ARMA filter until its zero initial state is forgotten
+    pub fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+        StandardNormal: Distribution<F>,
+    {
+        let radius = self
+            .arma
+            .ar_roots()
+            .into_iter()
+            .map(|root| root.norm().recip())
+            .fold(F::ZERO, F::max);
+        for _ in 0..burn_in(radius).max(Q) {
+            self.get_next(rng);
+        }
+    }
+}
+
+// gamma(0) = sigma^2 Gamma(1 - 2d) / Gamma(1 - d)^2 and
+// gamma(k) = gamma(k - 1) (k - 1 + d) / (k - d), up to `max_lag`
+fn fractional_autocovariance<F>(d: F, variance: F, max_lag: usize) -> Vec<F>
+where
+    F: Float,
+{
+    let mut gamma = Vec::with_capacity(max_lag + 1);
+    let gamma_0 =
+        variance * (ln_gamma(F::ONE - F::from(2.) * d) - F::from(2.) * ln_gamma(F::ONE - d)).exp();
+    gamma.push(gamma_0);
+    for k in 1..=max_lag {
+        let k = F::from(k as f64);
+        gamma.push(gamma[gamma.len() - 1] * (k - F::ONE + d) / (k - d));
+    }
+    gamma
+}
+
+impl<F, const P: usize, const Q: usize> TimeSeries<F> for Farima<F, P, Q>
+where
+    F: Float,
+    StandardNormal: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let u = self.arma.innovation_mut().get_next(rng);
+        self.arma.filter(u)
+    }
+}
+
+// the state of the ARMA filter and of the fractional noise driving it
+impl<F, const P: usize, const Q: usize> Stateful for Farima<F, P, Q>
+where
+    F: Float,
+{
+    type State = (([F; P], [F; Q]), (Vec<F>, Vec<F>, usize));
+
+    fn state(&self) -> Self::State {
+        (self.arma.state(), self.arma.innovation().state())
+    }
+
+    fn set_state(&mut self, (arma, noise): Self::State) {
+        self.arma.set_state(arma);
+        self.arma.innovation_mut().set_state(noise);
+    }
+
+    fn reset(&mut self) {
+        self.arma.reset();
+        self.arma.innovation_mut().reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    // sum x_t x_{t+k} / count within consecutive blocks of a zero mean series
+    fn block_autocovariance(data: &[f64], block_len: usize, max_lag: usize) -> Vec<f64> {
+        (0..=max_lag)
+            .map(|k| {
+                let (mut sum, mut count) = (0., 0);
+                for block in data.chunks(block_len) {
+                    for (x, y) in block.iter().zip(&block[k..]) {
+                        sum += x * y;
+                        count += 1;
+                    }
+                }
+                sum / count as f64
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fgn_autocovariance_works() {
+        // rho(1) = 2^(2H - 1) - 1
+        let fgn = FractionalGaussianNoise::new(0.8, 2., 16);
+        let gamma = fgn.autocovariance(3);
+        assert!((gamma[0] - 4.).abs() < 1e-12);
+        assert!((gamma[1] / gamma[0] - (2_f64.powf(0.6) - 1.)).abs() < 1e-12);
+
+        let white = FractionalGaussianNoise::new(0.5, 1., 16).autocovariance(3);
+        assert_eq!(white, vec![1., 0., 0., 0.]);
+    }
+
+    #[test]
+    fn davies_harte_matches_autocovariance() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+
+        for (hurst, block_len) in [(0.8, 1000), (0.3, 64)] {
+            let mut fgn = FractionalGaussianNoise::new(hurst, 1.5, block_len);
+            let data = std::iter::repeat_with(|| rng.get_next(&mut fgn))
+                .take(block_len * 200)
+                .collect::<Vec<_>>();
+
+            let gamma = block_autocovariance(&data, block_len, 10);
+            for (g, e) in gamma.iter().zip(fgn.autocovariance(10)) {
+                assert!((g - e).abs() < 0.05, "{hurst} {g} {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn hosking_matches_autocovariance() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let fgn = FractionalGaussianNoise::new(0.75, 1., 16);
+
+        let mut sum = [0.; 3];
+        let count = 20000;
+        for _ in 0..count {
+            let x = fgn.hosking(16, &mut rng);
+            sum[0] += x[15] * x[15];
+            sum[1] += x[14] * x[15];
+            sum[2] += x[0] * x[15];
+        }
+
+        let gamma = fgn.autocovariance(15);
+        for (s, e) in sum.iter().zip([gamma[0], gamma[1], gamma[15]]) {
+            assert!((s / count as f64 - e).abs() < 0.03);
+        }
+    }
+
+    #[test]
+    fn farima_matches_autocovariance() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let block_len = 1000;
+        let mut farima = Farima::new([], [], 0.8, 1., 0., block_len).unwrap();
+
+        // rho(1) = d / (1 - d) and gamma(0) = Gamma(1 - 2d) / Gamma(1 - d)^2
+        let gamma = farima.fractional_autocovariance(1);
+        let d = farima.d();
+        assert!((gamma[1] / gamma[0] - d / (1. - d)).abs() < 1e-12);
+        let expected = (ln_gamma(1. - 2. * d) - 2. * ln_gamma(1. - d)).exp();
+        assert!((gamma[0] - expected).abs() < 1e-12);
+
+        let data = std::iter::repeat_with(|| rng.get_next(&mut farima))
+            .take(block_len * 200)
+            .collect::<Vec<_>>();
+        let sample = block_autocovariance(&data, block_len, 10);
+        for (g, e) in sample.iter().zip(farima.fractional_autocovariance(10)) {
+            assert!((g - e).abs() < 0.05);
+        }
+
+        // the ARMA part is rejected as for Arma
+        assert!(Farima::new([1.2], [], 0.8, 1., 0., 16).is_err());
+    }
+
+    #[test]
+    fn state_forks_and_resets() {
+        // the fork starts within a block, whose remaining samples are part of
+        // the state
+        let mut farima = Farima::new([0.5], [], 0.8, 1., 0., 64).unwrap();
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let first = std::iter::repeat_with(|| rng.get_next(&mut farima))
+            .take(100)
+            .collect::<Vec<f64>>();
+
+        let state = farima.state();
+        let mut fork_rng = rng.clone();
+        let path = std::iter::repeat_with(|| rng.get_next(&mut farima))
+            .take(200)
+            .collect::<Vec<f64>>();
+        farima.set_state(state);
+        for &x in &path {
+            assert_eq!(fork_rng.get_next(&mut farima), x);
+        }
+
+        farima.reset();
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        for &x in &first {
+            assert_eq!(rng.get_next(&mut farima), x);
+        }
+    }
+}
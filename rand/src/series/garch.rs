@@ -0,0 +1,707 @@
+use core::fmt;
+
+use num::special::normal_cdf;
+use num::Float;
+
+use crate::distributions::{Distribution, Moments, StandardNormal};
+use crate::rngs::Rng;
+
+use super::{burn_in, spectral_radius, Arma, ArmaError, Stateful, TimeSeries, MAX_BURN_IN};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GarchError<F> {
+    // omega should be positive and the ARCH/GARCH coefficients non-negative
+    NegativeParameter,
+    // the volatility does not revert to a finite unconditional level
+    NonStationary { persistence: F },
+}
+
+impl<F> fmt::Display for GarchError<F>
+where
+    F: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NegativeParameter => write!(
+                f,
+                "omega should be positive and the coefficients non-negative"
+            ),
+            Self::NonStationary { persistence } => write!(
+                f,
+                "the volatility is not stationary, the persistence {persistence} should be less than 1"
+            ),
+        }
+    }
+}
+
+impl<F> std::error::Error for GarchError<F> where F: fmt::Debug + fmt::Display {}
+
+/// Conditionally heteroscedastic noise `e_t = sigma_t z_t`, the `z_t` being
+/// i.i.d. with zero mean and unit variance.
+pub trait Volatility<F>: TimeSeries<F> {
+    // sigma_t^2 of the next sample
+    fn conditional_variance(&self) -> F;
+
+    // rate at which the volatility forgets its past, less than 1 when stationary
+    fn persistence(&self) -> F;
+
+    // starts the volatility around its unconditional level and discards
+    // enough samples for the initial state to be forgotten
+    fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized;
+}
+
+/// GARCH(P, Q) and its GJR variant,
+/// `sigma_t^2 = omega + sum (alpha_i + gamma_i 1{e_{t-i} < 0}) e_{t-i}^2 + sum beta_j sigma_{t-j}^2`,
+/// the output being `mean + e_t`.
+#[derive(Clone)]
+pub struct Garch<F, const P: usize, const Q: usize, D = StandardNormal> {
+    omega: F,
+    alpha: [F; Q],
+    gamma: [F; Q],
+    beta: [F; P],
+    // e_{t-1}, ..., e_{t-Q}
+    e: [F; Q],
+    // sigma_{t-1}^2, ..., sigma_{t-P}^2
+    sigma2: [F; P],
+    innovation: D,
+    mean: F,
+}
+
+impl<F, const P: usize, const Q: usize> Garch<F, P, Q>
+where
+    F: Float,
+{
+    pub fn new(omega: F, alpha: [F; Q], beta: [F; P], mean: F) -> Result<Self, GarchError<F>> {
+        Self::with_innovation(omega, alpha, [F::ZERO; Q], beta, StandardNormal, mean)
+    }
+
+    // negative shocks raise the volatility by an extra gamma_i e_{t-i}^2
+    pub fn gjr(
+        omega: F,
+        alpha: [F; Q],
+        gamma: [F; Q],
+        beta: [F; P],
+        mean: F,
+    ) -> Result<Self, GarchError<F>> {
+        Self::with_innovation(omega, alpha, gamma, beta, StandardNormal, mean)
+    }
+}
+
+impl<F, const P: usize, const Q: usize, D> Garch<F, P, Q, D>
+where
+    F: Float,
+{
+    // the innovation should have zero mean and unit variance, and be
+    // symmetric for the GJR terms
+    pub fn with_innovation(
+        omega: F,
+        alpha: [F; Q],
+        gamma: [F; Q],
+        beta: [F; P],
+        innovation: D,
+        mean: F,
+    ) -> Result<Self, GarchError<F>> {
+        let garch = Self {
+            omega,
+            alpha,
+            gamma,
+            beta,
+            e: [F::ZERO; Q],
+            sigma2: [F::ZERO; P],
+            innovation,
+            mean,
+        };
+
+        let negative = garch
+            .alpha
+            .iter()
+            .zip(&garch.gamma)
+            .any(|(&a, &g)| a < F::ZERO || a + g < F::ZERO);
+        if omega <= F::ZERO || negative || garch.beta.iter().any(|&b| b < F::ZERO) {
+            return Err(GarchError::NegativeParameter);
+        }
+
+        let persistence = garch.persistence();
+        if persistence >= F::ONE {
+            return Err(GarchError::NonStationary { persistence });
+        }
+        Ok(garch)
+    }
+
+    // sum alpha_i + sum gamma_i / 2 + sum beta_j
+    pub fn persistence(&self) -> F {
+        let half = F::from(0.5);
+        let arch = self
+            .alpha
+            .iter()
+            .zip(&self.gamma)
+            .fold(F::ZERO, |acc, (&a, &g)| acc + a + half * g);
+        self.beta.iter().fold(arch, |acc, &b| acc + b)
+    }
+
+    pub fn conditional_variance(&self) -> F {
+        let mut sigma2 = self.omega;
+        for i in 0..Q {
+            let e = self.e[i];
+            let coefficient = if e < F::ZERO {
+                self.alpha[i] + self.gamma[i]
+            } else {
+                self.alpha[i]
+            };
+            sigma2 += coefficient * e * e;
+        }
+        for j in 0..P {
+            sigma2 += self.beta[j] * self.sigma2[j];
+        }
+        sigma2
+    }
+}
+
+impl<F, D> Garch<F, 1, 1, D>
+where
+    F: Float,
+{
+    // E e^4 / (E e^2)^2 given the kurtosis of the innovation (3 if Gaussian),
+    // infinite when the fourth moment does not exist.
+    // sigma_{t+1}^2 = omega + c_t sigma_t^2 with c_t = beta + (alpha + gamma 1{z_t < 0}) z_t^2,
+    // so with a = E c_t and b = E c_t^2, kurtosis = k (1 - a^2) / (1 - b)
+    pub fn kurtosis(&self, innovation_kurtosis: F) -> F {
+        let half = F::from(0.5);
+        let (alpha, gamma, beta) = (self.alpha[0], self.gamma[0], self.beta[0]);
+
+        let a = alpha + half * gamma + beta;
+        let b = beta * beta
+            + F::from(2.) * beta * (alpha + half * gamma)
+            + innovation_kurtosis * (alpha * alpha + alpha * gamma + half * gamma * gamma);
+
+        if b >= F::ONE {
+            return F::INFINITY;
+        }
+        innovation_kurtosis * (F::ONE - a * a) / (F::ONE - b)
+    }
+}
+
+impl<F, const P: usize, const Q: usize, D> Moments<F> for Garch<F, P, Q, D>
+where
+    F: Float,
+{
+    fn mean(&self) -> F {
+        self.mean
+    }
+
+    // omega / (1 - persistence)
+    fn variance(&self) -> F {
+        self.omega / (F::ONE - self.persistence())
+    }
+}
+
+impl<F, const P: usize, const Q: usize, D> TimeSeries<F> for Garch<F, P, Q, D>
+where
+    F: Float,
+    D: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let sigma2 = self.conditional_variance();
+        let e = sigma2.sqrt() * rng.sample(&self.innovation);
+
+        if Q > 0 {
+            self.e.rotate_right(1);
+            self.e[0] = e;
+        }
+
+        if P > 0 {
+            self.sigma2.rotate_right(1);
+            self.sigma2[0] = sigma2;
+        }
+
+        e + self.mean
+    }
+}
+
+// (e_{t-1}, ..., e_{t-Q}) and (sigma_{t-1}^2, ..., sigma_{t-P}^2)
+impl<F, const P: usize, const Q: usize, D> Stateful for Garch<F, P, Q, D>
+where
+    F: Float,
+{
+    type State = ([F; Q], [F; P]);
+
+    fn state(&self) -> Self::State {
+        (self.e, self.sigma2)
+    }
+
+    fn set_state(&mut self, (e, sigma2): Self::State) {
+        self.e = e;
+        self.sigma2 = sigma2;
+    }
+
+    fn reset(&mut self) {
+        self.set_state(([F::ZERO; Q], [F::ZERO; P]));
+    }
+}
+
+impl<F, const P: usize, const Q: usize, D> Volatility<F> for Garch<F, P, Q, D>
+where
+    F: Float,
+    D: Distribution<F>,
+{
+    fn conditional_variance(&self) -> F {
+        Garch::conditional_variance(self)
+    }
+
+    fn persistence(&self) -> F {
+        Garch::persistence(self)
+    }
+
+    fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+    {
+        let variance = self.variance();
+        self.sigma2 = [variance; P];
+        for e in &mut self.e {
+            *e = variance.sqrt() * rng.sample(&self.innovation);
+        }
+
+        for _ in 0..burn_in(self.persistence()) {
+            self.get_next(rng);
+        }
+    }
+}
+
+/// EGARCH(P, Q),
+/// `ln sigma_t^2 = omega + sum (alpha_i |z_{t-i}| + gamma_i z_{t-i}) + sum beta_j ln sigma_{t-j}^2`,
+/// the output being `mean + sigma_t z_t`. No sign constraint is needed on
+/// the coefficients, gamma_i < 0 gives the leverage effect.
+#[derive(Clone)]
+pub struct Egarch<F, const P: usize, const Q: usize, D = StandardNormal> {
+    omega: F,
+    alpha: [F; Q],
+    gamma: [F; Q],
+    beta: [F; P],
+    // z_{t-1}, ..., z_{t-Q}
+    z: [F; Q],
+    // ln sigma_{t-1}^2, ..., ln sigma_{t-P}^2
+    h: [F; P],
+    innovation: D,
+    mean: F,
+}
+
+impl<F, const P: usize, const Q: usize> Egarch<F, P, Q>
+where
+    F: Float,
+{
+    pub fn new(
+        omega: F,
+        alpha: [F; Q],
+        gamma: [F; Q],
+        beta: [F; P],
+        mean: F,
+    ) -> Result<Self, GarchError<F>> {
+        Self::with_innovation(omega, alpha, gamma, beta, StandardNormal, mean)
+    }
+}
+
+impl<F, const P: usize, const Q: usize, D> Egarch<F, P, Q, D>
+where
+    F: Float,
+{
+    // the innovation should have zero mean and unit variance
+    pub fn with_innovation(
+        omega: F,
+        alpha: [F; Q],
+        gamma: [F; Q],
+        beta: [F; P],
+        innovation: D,
+        mean: F,
+    ) -> Result<Self, GarchError<F>> {
+        let egarch = Self {
+            omega,
+            alpha,
+            gamma,
+            beta,
+            z: [F::ZERO; Q],
+            h: [F::ZERO; P],
+            innovation,
+            mean,
+        };
+
+        let persistence = egarch.persistence();
+        if persistence >= F::ONE {
+            return Err(GarchError::NonStationary { persistence });
+        }
+        Ok(egarch)
+    }
+
+    // spectral radius of the recursion on ln sigma_t^2
+    pub fn persistence(&self) -> F {
+        spectral_radius(&self.beta)
+    }
+
+    pub fn conditional_variance(&self) -> F {
+        self.ln_conditional_variance().exp()
+    }
+
+    fn ln_conditional_variance(&self) -> F {
+        let mut h = self.omega;
+        for i in 0..Q {
+            h += self.alpha[i] * self.z[i].abs() + self.gamma[i] * self.z[i];
+        }
+        for j in 0..P {
+            h += self.beta[j] * self.h[j];
+        }
+        h
+    }
+}
+
+impl<F, const P: usize, const Q: usize> Moments<F> for Egarch<F, P, Q>
+where
+    F: Float,
+{
+    fn mean(&self) -> F {
+        self.mean
+    }
+
+    // ln sigma_t^2 = omega / (1 - sum beta_j) + sum_k (a_k |z_{t-k}| + c_k z_{t-k}),
+    // a_k and c_k being alpha and gamma filtered by the AR weights pi of the
+    // beta recursion, and for z ~ N(0, 1)
+    // E exp(a |z| + c z) = e^((a+c)^2/2) Phi(a + c) + e^((a-c)^2/2) Phi(a - c)
+    fn variance(&self) -> F {
+        let half = F::from(0.5);
+        let beta_sum = self.beta.iter().fold(F::ZERO, |acc, &b| acc + b);
+        let mut ln_variance = self.omega / (F::ONE - beta_sum);
+
+        let mut pi = vec![F::ONE];
+        for k in 1..MAX_BURN_IN {
+            let mut p = F::ZERO;
+            for j in 1..=k.min(P) {
+                p += self.beta[j - 1] * pi[k - j];
+            }
+            pi.push(p);
+
+            let (mut a, mut c) = (F::ZERO, F::ZERO);
+            for i in 1..=k.min(Q) {
+                a += pi[k - i] * self.alpha[i - 1];
+                c += pi[k - i] * self.gamma[i - 1];
+            }
+            if k > Q && a.abs() + c.abs() < F::EPSILON {
+                break;
+            }
+
+            let (plus, minus) = (a + c, a - c);
+            let moment = (half * plus * plus).exp() * normal_cdf(plus)
+                + (half * minus * minus).exp() * normal_cdf(minus);
+            ln_variance += moment.ln();
+        }
+
+        ln_variance.exp()
+    }
+}
+
+impl<F, const P: usize, const Q: usize, D> TimeSeries<F> for Egarch<F, P, Q, D>
+where
+    F: Float,
+    D: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let h = self.ln_conditional_variance();
+        let z = rng.sample(&self.innovation);
+
+        if Q > 0 {
+            self.z.rotate_right(1);
+            self.z[0] = z;
+        }
+
+        if P > 0 {
+            self.h.rotate_right(1);
+            self.h[0] = h;
+        }
+
+        (F::from(0.5) * h).exp() * z + self.mean
+    }
+}
+
+// (z_{t-1}, ..., z_{t-Q}) and (ln sigma_{t-1}^2, ..., ln sigma_{t-P}^2)
+impl<F, const P: usize, const Q: usize, D> Stateful for Egarch<F, P, Q, D>
+where
+    F: Float,
+{
+    type State = ([F; Q], [F; P]);
+
+    fn state(&self) -> Self::State {
+        (self.z, self.h)
+    }
+
+    fn set_state(&mut self, (z, h): Self::State) {
+        self.z = z;
+        self.h = h;
+    }
+
+    fn reset(&mut self) {
+        self.set_state(([F::ZERO; Q], [F::ZERO; P]));
+    }
+}
+
+impl<F, const P: usize, const Q: usize, D> Volatility<F> for Egarch<F, P, Q, D>
+where
+    F: Float,
+    D: Distribution<F>,
+{
+    fn conditional_variance(&self) -> F {
+        Egarch::conditional_variance(self)
+    }
+
+    fn persistence(&self) -> F {
+        Egarch::persistence(self)
+    }
+
+    fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+    {
+        let beta_sum = self.beta.iter().fold(F::ZERO, |acc, &b| acc + b);
+        self.h = [self.omega / (F::ONE - beta_sum); P];
+        for z in &mut self.z {
+            *z = rng.sample(&self.innovation);
+        }
+
+        for _ in 0..burn_in(self.persistence()) {
+            self.get_next(rng);
+        }
+    }
+}
+
+/// ARMA(P, Q) driven by conditionally heteroscedastic noise, the mean of
+/// `x_t` follows the ARMA recursion and its variance the volatility `V`,
+/// which should have zero mean.
+#[derive(Clone)]
+pub struct ArmaGarch<F, const P: usize, const Q: usize, V> {
+    arma: Arma<F, P, Q, V>,
+}
+
+impl<F, const P: usize, const Q: usize, V> ArmaGarch<F, P, Q, V>
+where
+    F: Float,
+{
+    pub fn new(phi: [F; P], theta: [F; Q], volatility: V, mean: F) -> Result<Self, ArmaError<F>> {
+        let arma = Arma::with_innovation(phi, theta, volatility, mean)?;
+        Ok(Self { arma })
+    }
+
+    // the noise is white, so the ARMA moments only need its variance
+    pub fn arma(&self) -> &Arma<F, P, Q, V> {
+        &self.arma
+    }
+
+    pub fn volatility(&self) -> &V {
+        self.arma.innovation()
+    }
+}
+
+impl<F, const P: usize, const Q: usize, V> ArmaGarch<F, P, Q, V>
+where
+    F: Float,
+    V: Volatility<F>,
+{
+    pub fn conditional_variance(&self) -> F {
+        self.volatility().conditional_variance()
+    }
+
+    // initializes the volatility, then runs the ARMA filter until its zero
+    // initial state is forgotten
+    pub fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+    {
+        self.arma.innovation_mut().init_stationary(rng);
+
+        let radius = self
+            .arma
+            .ar_roots()
+            .into_iter()
+            .map(|root| root.norm().recip())
+            .fold(F::ZERO, F::max);
+        for _ in 0..burn_in(radius).max(Q) {
+            self.get_next(rng);
+        }
+    }
+}
+
+impl<F, const P: usize, const Q: usize, V> TimeSeries<F> for ArmaGarch<F, P, Q, V>
+where
+    F: Float,
+    V: TimeSeries<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let e = self.arma.innovation_mut().get_next(rng);
+        self.arma.filter(e)
+    }
+}
+
+// the state of the ARMA filter and of the volatility
+impl<F, const P: usize, const Q: usize, V> Stateful for ArmaGarch<F, P, Q, V>
+where
+    F: Float,
+    V: Stateful,
+{
+    type State = (([F; P], [F; Q]), V::State);
+
+    fn state(&self) -> Self::State {
+        (self.arma.state(), self.volatility().state())
+    }
+
+    fn set_state(&mut self, (arma, volatility): Self::State) {
+        self.arma.set_state(arma);
+        self.arma.innovation_mut().set_state(volatility);
+    }
+
+    fn reset(&mut self) {
+        self.arma.reset();
+        self.arma.innovation_mut().reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    fn sample<S: TimeSeries<f64>>(series: &mut S, count: usize) -> Vec<f64> {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        std::iter::repeat_with(|| rng.get_next(series))
+            .take(count)
+            .collect()
+    }
+
+    // mean, variance and kurtosis
+    fn moments(data: &[f64]) -> (f64, f64, f64) {
+        let n = data.len() as f64;
+        let mean = data.iter().sum::<f64>() / n;
+        let variance = data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        let fourth = data.iter().map(|x| (x - mean).powi(4)).sum::<f64>() / n;
+        (mean, variance, fourth / (variance * variance))
+    }
+
+    #[test]
+    fn rejects_invalid_parameters() {
+        assert_eq!(
+            Garch::new(0.1, [0.3], [0.8], 0.).err(),
+            Some(GarchError::NonStationary { persistence: 1.1 })
+        );
+        assert_eq!(
+            Garch::new(0.1, [-0.1], [0.8], 0.).err(),
+            Some(GarchError::NegativeParameter)
+        );
+        assert_eq!(
+            Garch::new(0., [0.1], [0.8], 0.).err(),
+            Some(GarchError::NegativeParameter)
+        );
+        assert!(Egarch::new(0., [0.2], [0.], [1.05], 0.).is_err());
+        assert!(Egarch::new(0., [0.2], [0.], [-0.95], 0.).is_ok());
+    }
+
+    #[test]
+    fn garch_matches_moments() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let (alpha, beta) = (0.1, 0.8);
+        let mut garch = Garch::new(0.1, [alpha], [beta], 2.).unwrap();
+        garch.init_stationary(&mut rng);
+
+        let data = sample(&mut garch, 400000);
+        let (mean, variance, kurtosis) = moments(&data);
+        assert!((mean - 2.).abs() < 0.01);
+        assert!((variance / garch.variance() - 1.).abs() < 0.03);
+        assert!((kurtosis / garch.kurtosis(3.) - 1.).abs() < 0.1);
+
+        // volatility clustering, rho_1 of e_t^2 is
+        // alpha (1 - alpha beta - beta^2) / (1 - 2 alpha beta - beta^2)
+        let squares = data.iter().map(|x| (x - mean).powi(2)).collect::<Vec<_>>();
+        let (m, v, _) = moments(&squares);
+        let c = squares
+            .iter()
+            .zip(&squares[1..])
+            .map(|(x, y)| (x - m) * (y - m))
+            .sum::<f64>()
+            / squares.len() as f64;
+        let expected =
+            alpha * (1. - alpha * beta - beta * beta) / (1. - 2. * alpha * beta - beta * beta);
+        assert!((c / v - expected).abs() < 0.02);
+    }
+
+    #[test]
+    fn gjr_matches_moments() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut gjr = Garch::gjr(0.1, [0.05], [0.1], [0.8], 0.).unwrap();
+        assert!((gjr.persistence() - 0.9).abs() < 1e-12);
+        gjr.init_stationary(&mut rng);
+
+        let data = sample(&mut gjr, 400000);
+        let (_, variance, kurtosis) = moments(&data);
+        assert!((variance / gjr.variance() - 1.).abs() < 0.03);
+        assert!((kurtosis / gjr.kurtosis(3.) - 1.).abs() < 0.1);
+
+        // a negative shock raises the volatility more than a positive one
+        let mut up = Garch::gjr(0.1, [0.05], [0.1], [0.8], 0.).unwrap();
+        let mut down = Garch::gjr(0.1, [0.05], [0.1], [0.8], 0.).unwrap();
+        up.e = [1.];
+        down.e = [-1.];
+        assert!(down.conditional_variance() > up.conditional_variance());
+    }
+
+    #[test]
+    fn egarch_matches_moments() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut egarch = Egarch::new(-0.1, [0.2], [-0.1], [0.9], 0.).unwrap();
+        egarch.init_stationary(&mut rng);
+
+        let data = sample(&mut egarch, 400000);
+        let (_, variance, _) = moments(&data);
+        assert!((variance / egarch.variance() - 1.).abs() < 0.03);
+
+        // without the beta recursion ln sigma^2 = omega + alpha |z| exactly
+        let egarch = Egarch::<f64, 0, 1>::new(0.5, [0.4], [0.], [], 0.).unwrap();
+        let expected = 2. * (0.5 + 0.08_f64).exp() * normal_cdf(0.4);
+        assert!((egarch.variance() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn arma_garch_matches_arma_moments() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let garch = Garch::new(0.3, [0.1], [0.8], 0.).unwrap();
+        let mut arma_garch = ArmaGarch::new([0.7], [-0.3], garch, 5.).unwrap();
+        arma_garch.init_stationary(&mut rng);
+
+        let data = sample(&mut arma_garch, 400000);
+        let (mean, variance, _) = moments(&data);
+        let arma = arma_garch.arma();
+        assert!((mean - arma.mean()).abs() < 0.05);
+        assert!((variance / arma.variance() - 1.).abs() < 0.05);
+
+        for (k, rho) in arma.autocorrelation(3).into_iter().enumerate() {
+            let c = data
+                .iter()
+                .zip(&data[k..])
+                .map(|(x, y)| (x - mean) * (y - mean))
+                .sum::<f64>()
+                / data.len() as f64;
+            assert!((c / variance - rho).abs() < 0.02);
+        }
+    }
+}
@@ -0,0 +1,597 @@
+use core::fmt;
+
+use num::linalg::Matrix;
+use num::Float;
+
+use crate::distributions::{Distribution, StandardUniformClosedOpen, StandardUniformOpenClosed};
+use crate::rngs::Rng;
+
+use super::{stationary_distribution, ArrivalProcess, Stateful, TimeSeries};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapError {
+    DimensionMismatch,
+    // negative rates outside the diagonal of D0, or rows of D0 + sum D_k
+    // that do not sum to zero
+    InvalidGenerator,
+    // the phase process is not irreducible or never produces an arrival
+    Singular,
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DimensionMismatch => write!(f, "the matrices should be square of the same size"),
+            Self::InvalidGenerator => write!(f, "D0 + sum D_k is not a generator"),
+            Self::Singular => write!(f, "the phase process has no stationary arrival rate"),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+// pi A, for a row vector pi
+fn row_mul<F>(pi: &[F], a: &Matrix<F>) -> Vec<F>
+where
+    F: Float,
+{
+    a.transpose()
+        .mul_vec(pi)
+        .expect("the dimensions should match")
+}
+
+fn sum<F>(x: &[F]) -> F
+where
+    F: Float,
+{
+    x.iter().fold(F::ZERO, |acc, &x| acc + x)
+}
+
+/// Batch Markovian arrival process: a CTMC on `m` phases whose transitions
+/// are either hidden (`D0`) or come with a batch of `k` arrivals (`D_k`).
+/// Each sample is the time since the previous batch and its size.
+#[derive(Clone)]
+pub struct BatchMap<F> {
+    d0: Matrix<F>,
+    // D_1, ..., D_K
+    d: Vec<Matrix<F>>,
+    // -D0_ii
+    rates: Vec<F>,
+    // per phase, the cumulative probabilities of the transitions out of it,
+    // with the next phase and the batch size (0 for the hidden ones)
+    transitions: Vec<Vec<(F, usize, usize)>>,
+    phase: usize,
+}
+
+impl<F> BatchMap<F>
+where
+    F: Float,
+{
+    pub fn new(d0: Matrix<F>, d: Vec<Matrix<F>>) -> Result<Self, MapError> {
+        let m = d0.rows();
+        if m == 0 || !d0.is_square() || d.iter().any(|d| d.rows() != m || d.cols() != m) {
+            return Err(MapError::DimensionMismatch);
+        }
+
+        let mut rates = Vec::with_capacity(m);
+        let mut transitions = Vec::with_capacity(m);
+        for i in 0..m {
+            let rate = -d0[(i, i)];
+            if rate <= F::ZERO {
+                return Err(MapError::InvalidGenerator);
+            }
+
+            let mut out = Vec::new();
+            let mut total = F::ZERO;
+            for j in (0..m).filter(|&j| j != i) {
+                out.push((d0[(i, j)], j, 0));
+            }
+            for (k, d) in d.iter().enumerate() {
+                for j in 0..m {
+                    out.push((d[(i, j)], j, k + 1));
+                }
+            }
+            for (r, _, _) in &mut out {
+                if *r < F::ZERO {
+                    return Err(MapError::InvalidGenerator);
+                }
+                total += *r;
+                *r = total / rate;
+            }
+            if (total - rate).abs() > F::from(1e-9) * rate {
+                return Err(MapError::InvalidGenerator);
+            }
+
+            rates.push(rate);
+            transitions.push(out);
+        }
+
+        let map = Self {
+            d0,
+            d,
+            rates,
+            transitions,
+            phase: 0,
+        };
+        if map.stationary().is_none() || map.batch_rate() <= F::ZERO {
+            return Err(MapError::Singular);
+        }
+        Ok(map)
+    }
+
+    pub fn phases(&self) -> usize {
+        self.rates.len()
+    }
+
+    // stationary distribution pi of the phase process, pi (D0 + sum D_k) = 0
+    fn stationary(&self) -> Option<Vec<F>> {
+        let m = self.phases();
+        let mut generator = self.d0.clone();
+        for d in &self.d {
+            for i in 0..m {
+                for j in 0..m {
+                    generator[(i, j)] += d[(i, j)];
+                }
+            }
+        }
+        stationary_distribution(generator)
+    }
+
+    pub fn stationary_phases(&self) -> Vec<F> {
+        self.stationary()
+            .expect("the phase process was checked to be irreducible")
+    }
+
+    // rate of the batches, pi sum D_k 1
+    pub fn batch_rate(&self) -> F {
+        let pi = self.stationary_phases();
+        self.d
+            .iter()
+            .fold(F::ZERO, |acc, d| acc + sum(&row_mul(&pi, d)))
+    }
+
+    // sum k pi D_k 1 / sum pi D_k 1
+    pub fn mean_batch_size(&self) -> F {
+        let pi = self.stationary_phases();
+        let (mut rate, mut arrivals) = (F::ZERO, F::ZERO);
+        for (k, d) in self.d.iter().enumerate() {
+            let r = sum(&row_mul(&pi, d));
+            rate += r;
+            arrivals += F::from((k + 1) as f64) * r;
+        }
+        arrivals / rate
+    }
+
+    // phase distribution right after a batch, pi sum D_k / lambda
+    fn embedded_stationary(&self) -> Vec<F> {
+        let pi = self.stationary_phases();
+        let mut phi = vec![F::ZERO; self.phases()];
+        for d in &self.d {
+            for (phi, x) in phi.iter_mut().zip(row_mul(&pi, d)) {
+                *phi += x;
+            }
+        }
+        let total = sum(&phi);
+        phi.iter().map(|&x| x / total).collect()
+    }
+
+    // draws the phase from its distribution right after a batch, so that the
+    // interarrival times are stationary from the first one
+    pub fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+        StandardUniformClosedOpen: Distribution<F>,
+    {
+        let u: F = rng.sample(&StandardUniformClosedOpen);
+        let mut cumulative = F::ZERO;
+        let phi = self.embedded_stationary();
+        self.phase = phi.len() - 1;
+        for (i, &p) in phi.iter().enumerate() {
+            cumulative += p;
+            if u < cumulative {
+                self.phase = i;
+                break;
+            }
+        }
+    }
+}
+
+impl<F> TimeSeries<(F, usize)> for BatchMap<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized,
+    {
+        let mut time = F::ZERO;
+        loop {
+            let i = self.phase;
+            let u: F = rng.sample(&StandardUniformOpenClosed);
+            time += -u.ln() / self.rates[i];
+
+            let u: F = rng.sample(&StandardUniformClosedOpen);
+            let transitions = &self.transitions[i];
+            // rounding may leave the last cumulative probability below 1
+            let &(_, j, batch) = transitions
+                .iter()
+                .find(|&&(p, _, _)| u < p)
+                .unwrap_or(&transitions[transitions.len() - 1]);
+
+            self.phase = j;
+            if batch > 0 {
+                return (time, batch);
+            }
+        }
+    }
+}
+
+/// Markovian arrival process MAP(D0, D1), every sample is an interarrival
+/// time. The rate, the moments and the autocorrelation of the interarrival
+/// times are known, see Neuts (1989).
+#[derive(Clone)]
+pub struct Map<F> {
+    batch: BatchMap<F>,
+}
+
+impl<F> Map<F>
+where
+    F: Float,
+{
+    pub fn new(d0: Matrix<F>, d1: Matrix<F>) -> Result<Self, MapError> {
+        let batch = BatchMap::new(d0, vec![d1])?;
+        Ok(Self { batch })
+    }
+
+    pub fn phases(&self) -> usize {
+        self.batch.phases()
+    }
+
+    pub fn stationary_phases(&self) -> Vec<F> {
+        self.batch.stationary_phases()
+    }
+
+    // lambda = pi D1 1
+    pub fn rate(&self) -> F {
+        self.batch.batch_rate()
+    }
+
+    // (-D0)^-1
+    fn fundamental(&self) -> Matrix<F> {
+        let mut minus_d0 = self.batch.d0.clone();
+        for i in 0..self.phases() {
+            for j in 0..self.phases() {
+                minus_d0[(i, j)] = -minus_d0[(i, j)];
+            }
+        }
+        minus_d0
+            .inverse()
+            .expect("-D0 is invertible when the phase process is irreducible")
+    }
+
+    // E[X^k] = k! phi (-D0)^-k 1, phi being the phase right after an arrival
+    pub fn moment(&self, k: u32) -> F {
+        let m = self.fundamental();
+        let mut v = vec![F::ONE; self.phases()];
+        let mut factorial = F::ONE;
+        for i in 1..=k {
+            v = m.mul_vec(&v).expect("the dimensions should match");
+            factorial *= F::from(i as f64);
+        }
+        let phi = self.batch.embedded_stationary();
+        factorial
+            * phi
+                .iter()
+                .zip(&v)
+                .fold(F::ZERO, |acc, (&p, &v)| acc + p * v)
+    }
+
+    // squared coefficient of variation of the interarrival times
+    pub fn scv(&self) -> F {
+        let mean = self.moment(1);
+        self.moment(2) / (mean * mean) - F::ONE
+    }
+
+    // rho(0..=max_lag) of the interarrival times, with
+    // E[X_0 X_k] = phi M P^k M 1, M = (-D0)^-1 and P = M D1 the transition
+    // matrix of the phase between arrivals
+    pub fn autocorrelation(&self, max_lag: usize) -> Vec<F> {
+        let m = self.fundamental();
+        let p = m
+            .mul(&self.batch.d[0])
+            .expect("the dimensions should match");
+
+        let mean = self.moment(1);
+        let variance = self.moment(2) - mean * mean;
+
+        let ones = vec![F::ONE; self.phases()];
+        let mut right = m.mul_vec(&ones).expect("the dimensions should match");
+        let left = row_mul(&self.batch.embedded_stationary(), &m);
+
+        let mut rho = vec![F::ONE];
+        for _ in 1..=max_lag {
+            right = p.mul_vec(&right).expect("the dimensions should match");
+            let product = left
+                .iter()
+                .zip(&right)
+                .fold(F::ZERO, |acc, (&l, &r)| acc + l * r);
+            rho.push((product - mean * mean) / variance);
+        }
+        rho
+    }
+
+    pub fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+        StandardUniformClosedOpen: Distribution<F>,
+    {
+        self.batch.init_stationary(rng);
+    }
+}
+
+impl<F> ArrivalProcess<F> for BatchMap<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn next_batch<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized,
+    {
+        self.get_next(rng)
+    }
+}
+
+// the current phase, the first one after a reset
+impl<F> Stateful for BatchMap<F> {
+    type State = usize;
+
+    fn state(&self) -> usize {
+        self.phase
+    }
+
+    fn set_state(&mut self, phase: usize) {
+        assert!(phase < self.rates.len(), "the phase should exist");
+        self.phase = phase;
+    }
+
+    fn reset(&mut self) {
+        self.phase = 0;
+    }
+}
+
+impl<F> TimeSeries<F> for Map<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        self.batch.get_next(rng).0
+    }
+}
+
+impl<F> Stateful for Map<F> {
+    type State = usize;
+
+    fn state(&self) -> usize {
+        self.batch.state()
+    }
+
+    fn set_state(&mut self, phase: usize) {
+        self.batch.set_state(phase);
+    }
+
+    fn reset(&mut self) {
+        self.batch.reset();
+    }
+}
+
+impl<F> ArrivalProcess<F> for Map<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn next_batch<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized,
+    {
+        (self.get_next(rng), 1)
+    }
+}
+
+/// Markov-modulated Poisson process: Poisson arrivals whose rate is
+/// `rates[i]` while the environment CTMC with generator `Q` is in state `i`.
+/// It is the MAP with D0 = Q - diag(rates) and D1 = diag(rates).
+#[derive(Clone)]
+pub struct Mmpp<F> {
+    map: Map<F>,
+}
+
+impl<F> Mmpp<F>
+where
+    F: Float,
+{
+    pub fn new(generator: &Matrix<F>, rates: &[F]) -> Result<Self, MapError> {
+        let m = rates.len();
+        if !generator.is_square() || generator.rows() != m {
+            return Err(MapError::DimensionMismatch);
+        }
+
+        let mut d0 = generator.clone();
+        let mut d1 = Matrix::zeros(m, m);
+        for (i, &rate) in rates.iter().enumerate() {
+            if rate < F::ZERO {
+                return Err(MapError::InvalidGenerator);
+            }
+            d0[(i, i)] -= rate;
+            d1[(i, i)] = rate;
+        }
+
+        let map = Map::new(d0, d1)?;
+        Ok(Self { map })
+    }
+
+    // the rate, moments and autocorrelation of the interarrival times
+    pub fn as_map(&self) -> &Map<F> {
+        &self.map
+    }
+
+    pub fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+        StandardUniformClosedOpen: Distribution<F>,
+    {
+        self.map.init_stationary(rng);
+    }
+}
+
+impl<F> TimeSeries<F> for Mmpp<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        self.map.get_next(rng)
+    }
+}
+
+// the state of the environment
+impl<F> Stateful for Mmpp<F> {
+    type State = usize;
+
+    fn state(&self) -> usize {
+        self.map.state()
+    }
+
+    fn set_state(&mut self, state: usize) {
+        self.map.set_state(state);
+    }
+
+    fn reset(&mut self) {
+        self.map.reset();
+    }
+}
+
+impl<F> ArrivalProcess<F> for Mmpp<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn next_batch<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized,
+    {
+        (self.get_next(rng), 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    #[test]
+    fn poisson_is_a_map() {
+        let map = Map::new(Matrix::from_rows(&[[-2.]]), Matrix::from_rows(&[[2.]])).unwrap();
+        assert!((map.rate() - 2.).abs() < 1e-12);
+        assert!((map.moment(1) - 0.5).abs() < 1e-12);
+        assert!((map.scv() - 1.).abs() < 1e-12);
+        assert!(map.autocorrelation(2)[1..]
+            .iter()
+            .all(|rho| rho.abs() < 1e-12));
+    }
+
+    #[test]
+    fn mmpp_matches_moments() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let generator = Matrix::from_rows(&[[-0.1, 0.1], [0.2, -0.2]]);
+        let mut mmpp = Mmpp::new(&generator, &[5., 0.5]).unwrap();
+        mmpp.init_stationary(&mut rng);
+
+        // pi = (2/3, 1/3)
+        let map = mmpp.as_map();
+        assert!((map.rate() - (2. / 3. * 5. + 1. / 3. * 0.5)).abs() < 1e-12);
+        assert!((map.moment(1) * map.rate() - 1.).abs() < 1e-12);
+
+        let count = 400000;
+        let data = std::iter::repeat_with(|| rng.get_next(&mut mmpp))
+            .take(count)
+            .collect::<Vec<f64>>();
+        assert!(data.iter().all(|&x| x > 0.));
+
+        let map = mmpp.as_map();
+        let mean = data.iter().sum::<f64>() / count as f64;
+        let variance = data.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / count as f64;
+        assert!((mean / map.moment(1) - 1.).abs() < 0.02);
+        assert!((variance / (mean * mean) / map.scv() - 1.).abs() < 0.05);
+
+        let rho = map.autocorrelation(3);
+        assert!(rho[1] > 0.1);
+        for (k, rho) in rho.into_iter().enumerate() {
+            let c = data
+                .iter()
+                .zip(&data[k..])
+                .map(|(x, y)| (x - mean) * (y - mean))
+                .sum::<f64>()
+                / count as f64;
+            assert!((c / variance - rho).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn batch_poisson() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let d = vec![Matrix::from_rows(&[[1.]]), Matrix::from_rows(&[[2.]])];
+        let mut bmap = BatchMap::new(Matrix::from_rows(&[[-3.]]), d).unwrap();
+        assert!((bmap.batch_rate() - 3.).abs() < 1e-12);
+        assert!((bmap.mean_batch_size() - 5. / 3.).abs() < 1e-12);
+
+        let count = 100000;
+        let (mut time, mut arrivals) = (0., 0);
+        for _ in 0..count {
+            let (interval, batch): (f64, usize) = rng.get_next(&mut bmap);
+            time += interval;
+            arrivals += batch;
+        }
+        assert!((count as f64 / time / 3. - 1.).abs() < 0.02);
+        assert!((arrivals as f64 / count as f64 / (5. / 3.) - 1.).abs() < 0.02);
+    }
+
+    #[test]
+    fn rejects_invalid_matrices() {
+        let d0 = Matrix::from_rows(&[[-1., 0.5], [0., -1.]]);
+        let d1 = Matrix::from_rows(&[[0.5, 0.], [0., 0.5]]);
+        assert_eq!(Map::new(d0, d1).err(), Some(MapError::InvalidGenerator));
+
+        let d0 = Matrix::from_rows(&[[-1.]]);
+        let d1 = Matrix::from_rows(&[[1., 0.]]);
+        assert_eq!(Map::new(d0, d1).err(), Some(MapError::DimensionMismatch));
+
+        // two closed classes, no stationary distribution
+        let generator = Matrix::from_rows(&[[0., 0.], [0., 0.]]);
+        assert_eq!(
+            Mmpp::new(&generator, &[1., 2.]).err(),
+            Some(MapError::Singular)
+        );
+    }
+}
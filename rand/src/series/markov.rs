@@ -0,0 +1,413 @@
+use core::fmt;
+
+use num::linalg::Matrix;
+use num::Float;
+
+use crate::distributions::{
+    Categorical, Distribution, Exponential, StandardUniformClosedOpen, StandardUniformOpenClosed,
+};
+use crate::rngs::Rng;
+
+use super::{stationary_distribution, Stateful, TimeSeries};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkovError {
+    // the matrix, the initial distribution and the values should have one
+    // entry per state
+    DimensionMismatch,
+    // rows of a transition matrix that are not probabilities, or rows of a
+    // generator with negative rates or that do not sum to zero
+    InvalidMatrix,
+    // the initial distribution is not a probability vector
+    InvalidInitial,
+}
+
+impl fmt::Display for MarkovError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DimensionMismatch => write!(f, "every state should have a row and a value"),
+            Self::InvalidMatrix => write!(f, "the matrix is not stochastic or not a generator"),
+            Self::InvalidInitial => write!(f, "the initial distribution is not a probability"),
+        }
+    }
+}
+
+impl std::error::Error for MarkovError {}
+
+fn check_initial<F>(initial: &[F], states: usize) -> Result<Categorical<F>, MarkovError>
+where
+    F: Float,
+{
+    if initial.len() != states {
+        return Err(MarkovError::DimensionMismatch);
+    }
+    let total = initial.iter().fold(F::ZERO, |acc, &p| acc + p);
+    if initial.iter().any(|&p| p < F::ZERO) || (total - F::ONE).abs() > F::from(1e-9) {
+        return Err(MarkovError::InvalidInitial);
+    }
+    Ok(Categorical::new(initial))
+}
+
+/// Discrete-time Markov chain with transition matrix `P`, emitting the value
+/// of each visited state. The first sample is the initial state.
+#[derive(Clone)]
+pub struct Dtmc<F, T> {
+    transitions: Matrix<F>,
+    // the rows of the transition matrix
+    rows: Vec<Categorical<F>>,
+    initial: Categorical<F>,
+    values: Vec<T>,
+    state: Option<usize>,
+}
+
+impl<F, T> Dtmc<F, T>
+where
+    F: Float,
+{
+    pub fn new(transitions: Matrix<F>, initial: &[F], values: Vec<T>) -> Result<Self, MarkovError> {
+        let m = transitions.rows();
+        if m == 0 || !transitions.is_square() || values.len() != m {
+            return Err(MarkovError::DimensionMismatch);
+        }
+        let initial = check_initial(initial, m)?;
+
+        let mut rows = Vec::with_capacity(m);
+        for i in 0..m {
+            let row = (0..m).map(|j| transitions[(i, j)]).collect::<Vec<_>>();
+            let total = row.iter().fold(F::ZERO, |acc, &p| acc + p);
+            if row.iter().any(|&p| p < F::ZERO) || (total - F::ONE).abs() > F::from(1e-9) {
+                return Err(MarkovError::InvalidMatrix);
+            }
+            rows.push(Categorical::new(&row));
+        }
+
+        Ok(Self {
+            transitions,
+            rows,
+            initial,
+            values,
+            state: None,
+        })
+    }
+
+    pub fn states(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    // pi P = pi, None if the chain has several closed classes
+    pub fn stationary(&self) -> Option<Vec<F>> {
+        let mut a = self.transitions.clone();
+        for i in 0..self.states() {
+            a[(i, i)] -= F::ONE;
+        }
+        stationary_distribution(a)
+    }
+
+    // the next sample is a state drawn from the stationary distribution
+    pub fn init_stationary(&mut self) {
+        let pi = self
+            .stationary()
+            .expect("the stationary distribution should be unique");
+        self.initial = Categorical::new(&pi);
+        self.state = None;
+    }
+
+    // moves to the next state and returns it
+    pub fn step<R>(&mut self, rng: &mut R) -> usize
+    where
+        R: Rng + ?Sized,
+        StandardUniformClosedOpen: Distribution<F>,
+    {
+        let state = match self.state {
+            None => rng.sample(&self.initial),
+            Some(i) => rng.sample(&self.rows[i]),
+        };
+        self.state = Some(state);
+        state
+    }
+}
+
+impl<F, T> TimeSeries<T> for Dtmc<F, T>
+where
+    F: Float,
+    T: Clone,
+    StandardUniformClosedOpen: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> T
+    where
+        R: Rng + ?Sized,
+    {
+        let state = self.step(rng);
+        self.values[state].clone()
+    }
+}
+
+// the current state, None before the first sample, which draws it from the
+// initial distribution
+impl<F, T> Stateful for Dtmc<F, T> {
+    type State = Option<usize>;
+
+    fn state(&self) -> Option<usize> {
+        self.state
+    }
+
+    fn set_state(&mut self, state: Option<usize>) {
+        assert!(
+            state.map_or(true, |state| state < self.values.len()),
+            "the state should exist"
+        );
+        self.state = state;
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Continuous-time Markov chain with generator `Q`. Each sample is the
+/// holding time in a state and the value of that state, starting with the
+/// initial one. Absorbing states are held forever.
+#[derive(Clone)]
+pub struct Ctmc<F, T> {
+    generator: Matrix<F>,
+    // None for the absorbing states
+    holding_times: Vec<Option<Exponential<F>>>,
+    // the rows of the embedded jump chain
+    jumps: Vec<Categorical<F>>,
+    initial: Categorical<F>,
+    values: Vec<T>,
+    state: Option<usize>,
+}
+
+impl<F, T> Ctmc<F, T>
+where
+    F: Float,
+{
+    pub fn new(generator: Matrix<F>, initial: &[F], values: Vec<T>) -> Result<Self, MarkovError> {
+        let m = generator.rows();
+        if m == 0 || !generator.is_square() || values.len() != m {
+            return Err(MarkovError::DimensionMismatch);
+        }
+        let initial = check_initial(initial, m)?;
+
+        let mut holding_times = Vec::with_capacity(m);
+        let mut jumps = Vec::with_capacity(m);
+        for i in 0..m {
+            let rate = -generator[(i, i)];
+            let mut row = (0..m).map(|j| generator[(i, j)]).collect::<Vec<_>>();
+            row[i] = F::ZERO;
+            let total = row.iter().fold(F::ZERO, |acc, &q| acc + q);
+            if row.iter().any(|&q| q < F::ZERO) || (total - rate).abs() > F::from(1e-9) * rate {
+                return Err(MarkovError::InvalidMatrix);
+            }
+
+            if rate > F::ZERO {
+                holding_times.push(Some(Exponential::new(rate.recip())));
+            } else {
+                holding_times.push(None);
+                row[i] = F::ONE;
+            }
+            jumps.push(Categorical::new(&row));
+        }
+
+        Ok(Self {
+            generator,
+            holding_times,
+            jumps,
+            initial,
+            values,
+            state: None,
+        })
+    }
+
+    pub fn states(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    // pi Q = 0, the fraction of time spent in each state. None if the chain
+    // has several closed classes.
+    pub fn stationary(&self) -> Option<Vec<F>> {
+        stationary_distribution(self.generator.clone())
+    }
+
+    // the next sample starts in a state drawn from the stationary
+    // distribution, with a full holding time
+    pub fn init_stationary(&mut self) {
+        let pi = self
+            .stationary()
+            .expect("the stationary distribution should be unique");
+        self.initial = Categorical::new(&pi);
+        self.state = None;
+    }
+
+    // holding time in the next state visited, and that state
+    pub fn step<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized,
+        StandardUniformClosedOpen: Distribution<F>,
+        StandardUniformOpenClosed: Distribution<F>,
+    {
+        let state = match self.state {
+            None => rng.sample(&self.initial),
+            Some(i) => rng.sample(&self.jumps[i]),
+        };
+        self.state = Some(state);
+        let holding_time = match &self.holding_times[state] {
+            Some(distribution) => rng.sample(distribution),
+            None => F::INFINITY,
+        };
+        (holding_time, state)
+    }
+}
+
+impl<F, T> TimeSeries<(F, T)> for Ctmc<F, T>
+where
+    F: Float,
+    T: Clone,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> (F, T)
+    where
+        R: Rng + ?Sized,
+    {
+        let (holding_time, state) = self.step(rng);
+        (holding_time, self.values[state].clone())
+    }
+}
+
+// the state of the last sample, None before the first one
+impl<F, T> Stateful for Ctmc<F, T> {
+    type State = Option<usize>;
+
+    fn state(&self) -> Option<usize> {
+        self.state
+    }
+
+    fn set_state(&mut self, state: Option<usize>) {
+        assert!(
+            state.map_or(true, |state| state < self.values.len()),
+            "the state should exist"
+        );
+        self.state = state;
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    #[test]
+    fn dtmc_frequencies() {
+        let p = Matrix::from_rows(&[[0.5, 0.5, 0.], [0.25, 0.5, 0.25], [0., 0.5, 0.5]]);
+        let mut chain = Dtmc::new(p, &[1., 0., 0.], vec!['a', 'b', 'c']).unwrap();
+
+        let pi = chain.stationary().unwrap();
+        for (&pi, expected) in pi.iter().zip([0.25, 0.5, 0.25]) {
+            assert!((pi - expected).abs() < 1e-12);
+        }
+
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        assert_eq!(rng.get_next(&mut chain), 'a');
+
+        let n = 200000;
+        let (mut counts, mut stays) = ([0; 3], 0);
+        let mut previous = 0;
+        for _ in 0..n {
+            let value: char = rng.get_next(&mut chain);
+            let state = chain.state().unwrap();
+            assert_eq!(value, chain.values()[state]);
+            counts[state] += 1;
+            if state == previous {
+                stays += 1;
+            }
+            previous = state;
+        }
+
+        for (&count, &pi) in counts.iter().zip(&pi) {
+            assert!((count as f64 / n as f64 - pi).abs() < 0.01);
+        }
+        // every state is kept with probability 1/2
+        assert!((stays as f64 / n as f64 - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn ctmc_up_down() {
+        // server failing at rate 0.1 and repaired at rate 2
+        let q = Matrix::from_rows(&[[-0.1, 0.1], [2., -2.]]);
+        let mut server = Ctmc::new(q, &[1., 0.], vec![true, false]).unwrap();
+
+        let pi = server.stationary().unwrap();
+        assert!((pi[0] - 2. / 2.1).abs() < 1e-12);
+
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        server.init_stationary();
+
+        let (mut up, mut down, mut repairs) = (0., 0., 0);
+        for _ in 0..200000 {
+            let (time, is_up): (f64, bool) = rng.get_next(&mut server);
+            if is_up {
+                up += time;
+            } else {
+                down += time;
+                repairs += 1;
+            }
+        }
+
+        assert!((up / (up + down) - pi[0]).abs() < 0.002);
+        assert!((down / repairs as f64 / 0.5 - 1.).abs() < 0.01);
+    }
+
+    #[test]
+    fn ctmc_absorbing_state() {
+        let q = Matrix::from_rows(&[[-1., 1.], [0., 0.]]);
+        let mut chain = Ctmc::new(q, &[1., 0.], vec![0, 1]).unwrap();
+        assert!(chain.stationary().is_some());
+
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let (time, _): (f64, _) = chain.step(&mut rng);
+        assert!(time.is_finite());
+        for _ in 0..3 {
+            assert_eq!(chain.step(&mut rng), (f64::INFINITY, 1));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_chains() {
+        let p = Matrix::from_rows(&[[0.5, 0.6], [0.5, 0.5]]);
+        assert_eq!(
+            Dtmc::new(p, &[1., 0.], vec![0, 1]).err(),
+            Some(MarkovError::InvalidMatrix)
+        );
+
+        let q = Matrix::from_rows(&[[-1., 1.], [1., -1.]]);
+        assert_eq!(
+            Ctmc::new(q.clone(), &[0.5, 0.4], vec![0, 1]).err(),
+            Some(MarkovError::InvalidInitial)
+        );
+        assert_eq!(
+            Ctmc::new(q, &[1., 0.], vec![0]).err(),
+            Some(MarkovError::DimensionMismatch)
+        );
+    }
+}
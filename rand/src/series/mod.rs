@@ -1,10 +1,184 @@
+use num::linalg::Matrix;
+use num::poly::roots;
+use num::Float;
+
 use crate::rngs::Rng;
 
+mod arima;
 mod arma;
-pub use arma::Arma;
+mod arrival;
+mod bootstrap;
+mod combinators;
+mod diffusion;
+pub mod fit;
+mod fractional;
+mod garch;
+mod map;
+mod markov;
+mod poisson;
+mod trace;
+mod traffic;
+pub use arima::{ArimaModel, Seasonal};
+pub use arma::{Arma, ArmaError, Forecast};
+pub use arrival::{ArrivalProcess, BatchArrivals, OnOff, Renewal};
+pub use bootstrap::{bootstrap_interval, Block, Bootstrap};
+pub use combinators::{
+    Abs, Aggregate, ClampMin, FromDistribution, Mapped, Scale, Shift, Superpose, Thin, Zip,
+};
+pub use diffusion::{
+    BrownianMotion, CoxIngersollRoss, Diffusion, GeometricBrownianMotion, Grid, OrnsteinUhlenbeck,
+};
+pub use fractional::{Farima, FractionalGaussianNoise};
+pub use garch::{ArmaGarch, Egarch, Garch, GarchError, Volatility};
+pub use map::{BatchMap, Map, MapError, Mmpp};
+pub use markov::{Ctmc, Dtmc, MarkovError};
+pub use poisson::{
+    BoundedRate, Intensity, NonHomogeneousPoisson, Piece, PiecewiseConstant, PiecewiseLinear,
+};
+pub use trace::{write_binary_trace, Replay, Trace, TraceError};
+pub use traffic::{Equilibrium, PacketCounts, ShiftedResidual, SuperposedOnOff, TailIndex};
 
 pub trait TimeSeries<T> {
     fn get_next<R>(&mut self, rng: &mut R) -> T
     where
         R: Rng + ?Sized;
+
+    fn map<U, G>(self, f: G) -> Mapped<Self, G, T>
+    where
+        Self: Sized,
+        G: FnMut(T) -> U,
+    {
+        Mapped::new(self, f)
+    }
+
+    fn abs(self) -> Abs<Self>
+    where
+        Self: Sized,
+        T: Float,
+    {
+        Abs::new(self)
+    }
+
+    fn scale(self, factor: T) -> Scale<Self, T>
+    where
+        Self: Sized,
+        T: Float,
+    {
+        Scale::new(self, factor)
+    }
+
+    fn shift(self, offset: T) -> Shift<Self, T>
+    where
+        Self: Sized,
+        T: Float,
+    {
+        Shift::new(self, offset)
+    }
+
+    fn clamp_min(self, min: T) -> ClampMin<Self, T>
+    where
+        Self: Sized,
+        T: Float,
+    {
+        ClampMin::new(self, min)
+    }
+
+    // pairs of samples of both series
+    fn zip<U, S>(self, other: S) -> Zip<Self, S>
+    where
+        Self: Sized,
+        S: TimeSeries<U>,
+    {
+        Zip::new(self, other)
+    }
+
+    // sums of k consecutive values
+    fn aggregate(self, k: usize) -> Aggregate<Self>
+    where
+        Self: Sized,
+        T: Float,
+    {
+        Aggregate::new(self, k)
+    }
+
+    // merges two streams of interarrival times
+    fn superpose<S>(self, other: S) -> Superpose<Self, S, T>
+    where
+        Self: Sized,
+        S: TimeSeries<T>,
+        T: Float,
+    {
+        Superpose::new(self, other)
+    }
+
+    // keeps each arrival of a stream of interarrival times with probability p
+    fn thin(self, p: T) -> Thin<Self, T>
+    where
+        Self: Sized,
+        T: Float,
+    {
+        Thin::new(self, p)
+    }
+}
+
+// series whose hidden state can be saved and restored, so that one model can
+// be reused across replications, or a sample path forked to feed the same
+// inputs to two policies
+pub trait Stateful {
+    type State;
+
+    fn state(&self) -> Self::State;
+    fn set_state(&mut self, state: Self::State);
+
+    // back to the state the series was built with
+    fn reset(&mut self);
+}
+
+// upper bound on the number of samples discarded by `init_stationary`
+const MAX_BURN_IN: usize = 100_000;
+
+// persistence^n < EPSILON
+fn burn_in<F>(persistence: F) -> usize
+where
+    F: Float,
+{
+    let mut n = 0;
+    let mut factor = F::ONE;
+    while factor > F::EPSILON && n < MAX_BURN_IN {
+        factor *= persistence;
+        n += 1;
+    }
+    n
+}
+
+// pi A = 0 with sum pi = 1, A being P - I or the generator of a Markov
+// chain. None when the solution is not unique, i.e. there are several closed
+// classes.
+fn stationary_distribution<F>(mut a: Matrix<F>) -> Option<Vec<F>>
+where
+    F: Float,
+{
+    let m = a.rows();
+    a = a.transpose();
+    // the equations are dependent, one is replaced by sum pi = 1
+    for j in 0..m {
+        a[(m - 1, j)] = F::ONE;
+    }
+    let mut b = vec![F::ZERO; m];
+    b[m - 1] = F::ONE;
+
+    a.solve(&b).ok()
+}
+
+// spectral radius of the recursion x_t = sum c_i x_{t-i}
+fn spectral_radius<F>(c: &[F]) -> F
+where
+    F: Float,
+{
+    let mut p = vec![F::ONE];
+    p.extend(c.iter().map(|&c| -c));
+    roots(&p)
+        .into_iter()
+        .map(|root| root.norm().recip())
+        .fold(F::ZERO, F::max)
 }
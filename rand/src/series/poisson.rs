@@ -0,0 +1,555 @@
+use num::Float;
+
+use crate::distributions::{Distribution, StandardUniformClosedOpen, StandardUniformOpenClosed};
+use crate::rngs::Rng;
+
+use super::{ArrivalProcess, Stateful, TimeSeries};
+
+/// Time-varying arrival rate, with piecewise-constant upper bounds used for
+/// thinning.
+pub trait Intensity<F> {
+    fn rate(&self, t: F) -> F;
+
+    // the piece of the majorant holding t
+    fn locate(&self, t: F) -> Piece;
+
+    // (bound, until) with rate(s) <= bound over the piece, which ends at until
+    fn majorant(&self, piece: Piece) -> (F, F);
+
+    fn next_piece(&self, piece: Piece) -> Piece;
+}
+
+// a piece of a majorant, numbered by its cycle for the periodic rates and its
+// index within the cycle. Thinning moves from piece to piece with these
+// integers, as the cycle of an end time recomputed in floating point can be
+// off by one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece {
+    pub cycle: u64,
+    pub index: usize,
+}
+
+/// Rate given by a function, bounded by `max_rate` at all times.
+#[derive(Clone)]
+pub struct BoundedRate<G, F> {
+    rate: G,
+    max_rate: F,
+}
+
+impl<G, F> BoundedRate<G, F>
+where
+    G: Fn(F) -> F,
+    F: Float,
+{
+    pub fn new(rate: G, max_rate: F) -> Self {
+        Self { rate, max_rate }
+    }
+}
+
+impl<G, F> Intensity<F> for BoundedRate<G, F>
+where
+    G: Fn(F) -> F,
+    F: Float,
+{
+    fn rate(&self, t: F) -> F {
+        (self.rate)(t)
+    }
+
+    fn locate(&self, _t: F) -> Piece {
+        Piece { cycle: 0, index: 0 }
+    }
+
+    fn majorant(&self, _piece: Piece) -> (F, F) {
+        (self.max_rate, F::INFINITY)
+    }
+
+    fn next_piece(&self, piece: Piece) -> Piece {
+        piece
+    }
+}
+
+// breakpoints of a rate table, shared by the piecewise constant and linear
+// rates. times[0] = 0, and past the last breakpoint the table either repeats
+// with the given period or keeps its last value.
+#[derive(Clone)]
+struct Table<F> {
+    times: Vec<F>,
+    rates: Vec<F>,
+    period: Option<F>,
+}
+
+impl<F> Table<F>
+where
+    F: Float,
+{
+    fn new(times: Vec<F>, rates: Vec<F>, period: Option<F>) -> Self {
+        assert!(
+            !times.is_empty() && times.len() == rates.len(),
+            "there should be one rate per breakpoint"
+        );
+        assert!(times[0] == F::ZERO, "the first breakpoint should be 0");
+        assert!(
+            times.windows(2).all(|w| w[0] < w[1]),
+            "the breakpoints should be increasing"
+        );
+        assert!(
+            rates.iter().all(|&r| r >= F::ZERO),
+            "the rates should be non-negative"
+        );
+        if let Some(period) = period {
+            assert!(
+                period > times[times.len() - 1],
+                "the period should be after the last breakpoint"
+            );
+        }
+
+        Self {
+            times,
+            rates,
+            period,
+        }
+    }
+
+    // the segment holding t, and t within its cycle
+    fn locate(&self, t: F) -> (Piece, F) {
+        let Some(period) = self.period else {
+            let s = t.max(F::ZERO);
+            let index = self.times.partition_point(|&time| time <= s) - 1;
+            return (Piece { cycle: 0, index }, s);
+        };
+
+        let mut cycle = (t / period).floor().max(F::ZERO).into_f64() as u64;
+        // rounding can leave t just outside [0, period) from its cycle
+        let mut s = (t - self.offset(cycle)).max(F::ZERO);
+        if s >= period {
+            cycle += 1;
+            s = F::ZERO;
+        }
+        let index = self.times.partition_point(|&time| time <= s) - 1;
+        let mut piece = Piece { cycle, index };
+
+        // and t can round to the end of its piece
+        while self.until(piece) <= t {
+            piece = self.next(piece);
+            s = self.times[piece.index];
+        }
+        (piece, s)
+    }
+
+    fn offset(&self, cycle: u64) -> F {
+        match self.period {
+            Some(period) => F::from(cycle as f64) * period,
+            None => F::ZERO,
+        }
+    }
+
+    // end of segment i within its cycle
+    fn end(&self, i: usize) -> F {
+        match (self.times.get(i + 1), self.period) {
+            (Some(&end), _) => end,
+            (None, Some(period)) => period,
+            (None, None) => F::INFINITY,
+        }
+    }
+
+    fn until(&self, piece: Piece) -> F {
+        self.offset(piece.cycle) + self.end(piece.index)
+    }
+
+    fn next(&self, piece: Piece) -> Piece {
+        if piece.index + 1 < self.times.len() {
+            Piece {
+                index: piece.index + 1,
+                ..piece
+            }
+        } else if self.period.is_some() {
+            Piece {
+                cycle: piece.cycle + 1,
+                index: 0,
+            }
+        } else {
+            piece
+        }
+    }
+
+    // rate at the end of segment i, wrapping to the first one
+    fn next_rate(&self, i: usize) -> F {
+        match (self.rates.get(i + 1), self.period) {
+            (Some(&rate), _) => rate,
+            (None, Some(_)) => self.rates[0],
+            (None, None) => self.rates[i],
+        }
+    }
+}
+
+/// Piecewise-constant rate, `rates[i]` on `[times[i], times[i + 1])`.
+#[derive(Clone)]
+pub struct PiecewiseConstant<F> {
+    table: Table<F>,
+}
+
+impl<F> PiecewiseConstant<F>
+where
+    F: Float,
+{
+    // times[0] should be 0, with a period the table repeats itself
+    pub fn new(times: Vec<F>, rates: Vec<F>, period: Option<F>) -> Self {
+        Self {
+            table: Table::new(times, rates, period),
+        }
+    }
+}
+
+impl<F> Intensity<F> for PiecewiseConstant<F>
+where
+    F: Float,
+{
+    fn rate(&self, t: F) -> F {
+        let (piece, _) = self.table.locate(t);
+        self.table.rates[piece.index]
+    }
+
+    fn locate(&self, t: F) -> Piece {
+        self.table.locate(t).0
+    }
+
+    fn majorant(&self, piece: Piece) -> (F, F) {
+        (self.table.rates[piece.index], self.table.until(piece))
+    }
+
+    fn next_piece(&self, piece: Piece) -> Piece {
+        self.table.next(piece)
+    }
+}
+
+/// Piecewise-linear rate, interpolating `rates[i]` at `times[i]`. With a
+/// period the last segment goes back to `rates[0]`, otherwise the rate
+/// stays at its last value.
+#[derive(Clone)]
+pub struct PiecewiseLinear<F> {
+    table: Table<F>,
+}
+
+impl<F> PiecewiseLinear<F>
+where
+    F: Float,
+{
+    // times[0] should be 0, with a period the table repeats itself
+    pub fn new(times: Vec<F>, rates: Vec<F>, period: Option<F>) -> Self {
+        Self {
+            table: Table::new(times, rates, period),
+        }
+    }
+}
+
+impl<F> Intensity<F> for PiecewiseLinear<F>
+where
+    F: Float,
+{
+    fn rate(&self, t: F) -> F {
+        let (Piece { index: i, .. }, s) = self.table.locate(t);
+        let (a, b) = (self.table.rates[i], self.table.next_rate(i));
+        let (start, end) = (self.table.times[i], self.table.end(i));
+        if end == F::INFINITY {
+            return a;
+        }
+        let x = (s - start) / (end - start);
+        a + (b - a) * x
+    }
+
+    fn locate(&self, t: F) -> Piece {
+        self.table.locate(t).0
+    }
+
+    fn majorant(&self, piece: Piece) -> (F, F) {
+        let i = piece.index;
+        let bound = self.table.rates[i].max(self.table.next_rate(i));
+        (bound, self.table.until(piece))
+    }
+
+    fn next_piece(&self, piece: Piece) -> Piece {
+        self.table.next(piece)
+    }
+}
+
+/// Poisson process with a time-varying rate, generated by Lewis-Shedler
+/// thinning: candidates are drawn from a homogeneous process with the rate
+/// of the majorant and accepted with probability rate / majorant.
+///
+/// Samples are interarrival times, the process keeps the time of its last
+/// arrival so the rate is evaluated at the right instant.
+#[derive(Clone)]
+pub struct NonHomogeneousPoisson<F, I> {
+    intensity: I,
+    time: F,
+}
+
+impl<F, I> NonHomogeneousPoisson<F, I>
+where
+    F: Float,
+    I: Intensity<F>,
+{
+    // starts at time 0
+    pub fn new(intensity: I) -> Self {
+        Self {
+            intensity,
+            time: F::ZERO,
+        }
+    }
+
+    pub fn intensity(&self) -> &I {
+        &self.intensity
+    }
+
+    // time of the next arrival, infinite if the rate is zero from then on
+    pub fn next_arrival<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+        StandardUniformClosedOpen: Distribution<F>,
+        StandardUniformOpenClosed: Distribution<F>,
+    {
+        let mut t = self.time;
+        let mut piece = self.intensity.locate(t);
+        // first piece of the current run of zero bounds, the rate is zero from
+        // then on once the run covers a whole cycle
+        let mut zero_since = None;
+        loop {
+            let (bound, until) = self.intensity.majorant(piece);
+            debug_assert!(until > t, "the piece should end after t");
+            if bound <= F::ZERO {
+                let start = *zero_since.get_or_insert(piece);
+                let cycled = piece.cycle > start.cycle && piece.index >= start.index;
+                if until == F::INFINITY || cycled {
+                    self.time = F::INFINITY;
+                    return F::INFINITY;
+                }
+                t = until;
+                piece = self.intensity.next_piece(piece);
+                continue;
+            }
+            zero_since = None;
+
+            let u: F = rng.sample(&StandardUniformOpenClosed);
+            let candidate = t - u.ln() / bound;
+            // the exponential is memoryless, so the search can restart at
+            // the end of the segment with its own bound
+            if candidate >= until {
+                t = until;
+                piece = self.intensity.next_piece(piece);
+                continue;
+            }
+
+            t = candidate;
+            let u: F = rng.sample(&StandardUniformClosedOpen);
+            if u * bound < self.intensity.rate(t) {
+                self.time = t;
+                return t;
+            }
+        }
+    }
+}
+
+// the time of the last arrival
+impl<F, I> Stateful for NonHomogeneousPoisson<F, I>
+where
+    F: Float,
+{
+    type State = F;
+
+    fn state(&self) -> F {
+        self.time
+    }
+
+    fn set_state(&mut self, time: F) {
+        self.time = time;
+    }
+
+    fn reset(&mut self) {
+        self.time = F::ZERO;
+    }
+}
+
+impl<F, I> TimeSeries<F> for NonHomogeneousPoisson<F, I>
+where
+    F: Float,
+    I: Intensity<F>,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let previous = self.time;
+        self.next_arrival(rng) - previous
+    }
+}
+
+impl<F, I> ArrivalProcess<F> for NonHomogeneousPoisson<F, I>
+where
+    F: Float,
+    I: Intensity<F>,
+    StandardUniformClosedOpen: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn next_batch<R>(&mut self, rng: &mut R) -> (F, usize)
+    where
+        R: Rng + ?Sized,
+    {
+        (self.get_next(rng), 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    // arrivals in each of the `bins` intervals of [0, horizon)
+    fn counts<I: Intensity<f64>>(
+        process: &mut NonHomogeneousPoisson<f64, I>,
+        rng: &mut Xoshiro256PlusPlus,
+        horizon: f64,
+        bins: usize,
+    ) -> Vec<usize> {
+        let mut counts = vec![0; bins];
+        loop {
+            let t = process.next_arrival(rng);
+            if t >= horizon {
+                return counts;
+            }
+            counts[(t / horizon * bins as f64) as usize] += 1;
+        }
+    }
+
+    #[test]
+    fn piecewise_constant_rates() {
+        let intensity = PiecewiseConstant::new(vec![0., 5.], vec![1., 4.], Some(10.));
+        assert_eq!(intensity.rate(12.), 1.);
+        assert_eq!(intensity.rate(17.), 4.);
+        assert_eq!(intensity.majorant(intensity.locate(17.)), (4., 20.));
+
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut process = NonHomogeneousPoisson::new(intensity);
+        let periods = 2000;
+        let bins = counts(&mut process, &mut rng, 10. * periods as f64, 2 * periods);
+
+        let low = bins.iter().step_by(2).sum::<usize>() as f64;
+        let high = bins.iter().skip(1).step_by(2).sum::<usize>() as f64;
+        assert!((low / (5. * periods as f64) - 1.).abs() < 0.03);
+        assert!((high / (20. * periods as f64) - 1.).abs() < 0.02);
+    }
+
+    #[test]
+    fn periods_without_exact_representation() {
+        for period in [0.7, 2.2, 86400. * 0.7] {
+            let intensity =
+                PiecewiseConstant::new(vec![0., 0.45 * period], vec![1., 3.], Some(period));
+
+            // the pieces follow each other without getting stuck at a cycle
+            // boundary, wherever the search starts again
+            let mut piece = intensity.locate(0.);
+            for _ in 0..200000 {
+                let (_, until) = intensity.majorant(piece);
+                let located = intensity.locate(until);
+                assert!(intensity.majorant(located).1 > until);
+                piece = intensity.next_piece(piece);
+            }
+            assert_eq!(piece.cycle, 100000);
+
+            // mean rate 0.45 + 3 * 0.55 = 2.1 per unit of time, scaled to
+            // 2.1 arrivals per cycle
+            let scaled = PiecewiseConstant::new(
+                vec![0., 0.45 * period],
+                vec![1. / period, 3. / period],
+                Some(period),
+            );
+            let mut rng = Xoshiro256PlusPlus::new(SEED);
+            let mut process = NonHomogeneousPoisson::new(scaled);
+            let cycles = 20000.;
+            let count = counts(&mut process, &mut rng, cycles * period, 1)[0];
+            assert!((count as f64 / (2.1 * cycles) - 1.).abs() < 0.02);
+        }
+
+        // stuck for good after 20 arrivals when the cycle was recomputed
+        // from the end times
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let intensity = PiecewiseConstant::new(vec![0., 1.], vec![1., 3.], Some(2.2));
+        let mut process = NonHomogeneousPoisson::new(intensity);
+        for _ in 0..10000 {
+            process.next_arrival(&mut rng);
+        }
+        assert!(process.state() > 2000.);
+    }
+
+    #[test]
+    fn zero_periodic_rates() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let intensity = PiecewiseConstant::new(vec![0., 1.], vec![0., 0.], Some(2.2));
+        let mut process = NonHomogeneousPoisson::new(intensity);
+        assert_eq!(process.next_arrival(&mut rng), f64::INFINITY);
+
+        let intensity = PiecewiseLinear::new(vec![0., 1.], vec![0., 0.], Some(2.2));
+        let mut process = NonHomogeneousPoisson::new(intensity);
+        process.set_state(5.);
+        assert_eq!(process.next_arrival(&mut rng), f64::INFINITY);
+
+        // a zero rate over most of the cycle is still crossed
+        let intensity = PiecewiseConstant::new(vec![0., 1.], vec![0., 1e-3], Some(2.));
+        let mut process = NonHomogeneousPoisson::new(intensity);
+        assert!(process.next_arrival(&mut rng) < f64::INFINITY);
+    }
+
+    #[test]
+    fn piecewise_linear_rates() {
+        // ramps from 0 to 2 over [0, 10), then stays at 2
+        let intensity = PiecewiseLinear::new(vec![0., 10.], vec![0., 2.], None);
+        assert_eq!(intensity.rate(2.5), 0.5);
+        assert_eq!(intensity.rate(50.), 2.);
+
+        // expected counts 10 on [0, 10) and 20 on [10, 20)
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let (mut first, mut second) = (0, 0);
+        for _ in 0..2000 {
+            let mut process =
+                NonHomogeneousPoisson::new(PiecewiseLinear::new(vec![0., 10.], vec![0., 2.], None));
+            let bins = counts(&mut process, &mut rng, 20., 2);
+            first += bins[0];
+            second += bins[1];
+        }
+        assert!((first as f64 / 20000. - 1.).abs() < 0.03);
+        assert!((second as f64 / 40000. - 1.).abs() < 0.03);
+
+        // periodic, back to rates[0] at the end of the period
+        let periodic = PiecewiseLinear::new(vec![0., 10.], vec![0., 2.], Some(20.));
+        assert_eq!(periodic.rate(15.), 1.);
+        assert_eq!(periodic.rate(22.5), 0.5);
+    }
+
+    #[test]
+    fn bounded_rate_function() {
+        // 1 + sin(t), with an expected count of 2 pi per period
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let intensity = BoundedRate::new(|t: f64| 1. + t.sin(), 2.);
+        let mut process = NonHomogeneousPoisson::new(intensity);
+
+        let periods = 2000;
+        let horizon = 2. * core::f64::consts::PI * periods as f64;
+        let bins = counts(&mut process, &mut rng, horizon, 4 * periods);
+        let total = bins.iter().sum::<usize>() as f64;
+        assert!((total / horizon - 1.).abs() < 0.02);
+
+        // the first quarter of each period has the highest rate
+        let quarter = bins.iter().step_by(4).sum::<usize>() as f64;
+        let expected = (core::f64::consts::FRAC_PI_2 + 1.) * periods as f64;
+        assert!((quarter / expected - 1.).abs() < 0.03);
+        assert!((process.state() - horizon).abs() < 10.);
+    }
+}
@@ -0,0 +1,280 @@
+use core::fmt;
+use std::io;
+use std::path::Path;
+
+use num::Float;
+
+use crate::distributions::{Distribution, StandardUniformClosedOpen, UniformInt};
+use crate::rngs::Rng;
+
+use super::bootstrap::{Block, Blocks};
+use super::{Stateful, TimeSeries};
+
+#[derive(Debug)]
+pub enum TraceError {
+    Io(io::Error),
+    // a line of a CSV file, counted from 1, that is not a number
+    Parse { line: usize },
+    // the size of a binary file is not a multiple of 8 bytes
+    Truncated,
+    Empty,
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "cannot read the trace: {error}"),
+            Self::Parse { line } => write!(f, "line {line} is not a number"),
+            Self::Truncated => write!(f, "the binary trace does not hold whole f64 values"),
+            Self::Empty => write!(f, "the trace has no values"),
+        }
+    }
+}
+
+impl std::error::Error for TraceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TraceError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Replay<F> {
+    // from the first value, starting over at the end
+    Loop,
+    // from a uniformly drawn value, starting over at the end
+    RandomOffset,
+    // blocks of consecutive values from random starting points
+    Bootstrap(Block<F>),
+}
+
+/// Replay of recorded values, e.g. measured interarrival or service times.
+///
+/// The CSV format is the one written by the `sim` binaries: an optional
+/// header line, then one value per line. The binary format is a sequence of
+/// little-endian f64 values without header.
+#[derive(Clone)]
+pub struct Trace<F> {
+    values: Vec<F>,
+    replay: Replay<F>,
+    start: UniformInt<usize>,
+    // None until the first sample
+    position: Option<usize>,
+    // the bootstrap replays
+    blocks: Option<Blocks<F>>,
+}
+
+impl<F> Trace<F>
+where
+    F: Float,
+{
+    pub fn new(values: Vec<F>, replay: Replay<F>) -> Result<Self, TraceError> {
+        if values.is_empty() {
+            return Err(TraceError::Empty);
+        }
+        let blocks = match replay {
+            Replay::Bootstrap(block) => Some(Blocks::new(block, values.len())),
+            _ => None,
+        };
+
+        Ok(Self {
+            start: UniformInt::<usize>::new(0, values.len()),
+            values,
+            replay,
+            position: None,
+            blocks,
+        })
+    }
+
+    pub fn from_csv(path: impl AsRef<Path>, replay: Replay<F>) -> Result<Self, TraceError> {
+        let text = std::fs::read_to_string(path)?;
+
+        let mut values = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line.parse::<f64>() {
+                Ok(x) => values.push(F::from(x)),
+                // the header
+                Err(_) if i == 0 => {}
+                Err(_) => return Err(TraceError::Parse { line: i + 1 }),
+            }
+        }
+
+        Self::new(values, replay)
+    }
+
+    pub fn from_binary(path: impl AsRef<Path>, replay: Replay<F>) -> Result<Self, TraceError> {
+        let bytes = std::fs::read(path)?;
+        if bytes.len() % 8 != 0 {
+            return Err(TraceError::Truncated);
+        }
+
+        let values = bytes
+            .chunks_exact(8)
+            .map(|b| F::from(f64::from_le_bytes(b.try_into().unwrap())))
+            .collect();
+        Self::new(values, replay)
+    }
+
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    // index following the last value, None before the first sample
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+}
+
+// writes values in the binary format read by `Trace::from_binary`
+pub fn write_binary_trace(path: impl AsRef<Path>, values: &[f64]) -> io::Result<()> {
+    let bytes = values
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect::<Vec<_>>();
+    std::fs::write(path, bytes)
+}
+
+impl<F> TimeSeries<F> for Trace<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let position = match (&mut self.blocks, self.position) {
+            (Some(blocks), _) => blocks.next_index(rng),
+            (None, Some(position)) => position,
+            (None, None) if self.replay == Replay::Loop => 0,
+            (None, None) => rng.sample(&self.start),
+        };
+
+        self.position = Some(if position + 1 == self.values.len() {
+            0
+        } else {
+            position + 1
+        });
+        self.values[position]
+    }
+}
+
+// the index of the next value, None before the first sample, and for the
+// bootstrap replays the values left in the current block
+impl<F> Stateful for Trace<F> {
+    type State = (Option<usize>, usize);
+
+    fn state(&self) -> Self::State {
+        match &self.blocks {
+            Some(blocks) => blocks.state(),
+            None => (self.position, 0),
+        }
+    }
+
+    fn set_state(&mut self, (position, left): Self::State) {
+        assert!(
+            position.map_or(true, |position| position < self.values.len()),
+            "the position should be in the trace"
+        );
+        if let Some(blocks) = &mut self.blocks {
+            blocks.set_state((position, left));
+        }
+        self.position = position;
+    }
+
+    fn reset(&mut self) {
+        self.set_state((None, 0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    fn sample(trace: &mut Trace<f64>, n: usize) -> Vec<f64> {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        (0..n).map(|_| trace.get_next(&mut rng)).collect()
+    }
+
+    #[test]
+    fn replay_modes() {
+        let values = (0..10).map(|i| i as f64).collect::<Vec<_>>();
+
+        let mut trace = Trace::new(values.clone(), Replay::Loop).unwrap();
+        let expected = (0..25).map(|i| (i % 10) as f64).collect::<Vec<_>>();
+        assert_eq!(sample(&mut trace, 25), expected);
+
+        let mut trace = Trace::new(values.clone(), Replay::RandomOffset).unwrap();
+        let samples = sample(&mut trace, 25);
+        for pair in samples.windows(2) {
+            assert_eq!(pair[1], (pair[0] + 1.) % 10.);
+        }
+
+        // blocks of 4 consecutive values, wrapping around
+        let replay = Replay::Bootstrap(Block::Circular { len: 4 });
+        let mut trace = Trace::new(values, replay).unwrap();
+        let samples = sample(&mut trace, 4000);
+        let mut starts = [0; 10];
+        for block in samples.chunks_exact(4) {
+            starts[block[0] as usize] += 1;
+            for pair in block.windows(2) {
+                assert_eq!(pair[1], (pair[0] + 1.) % 10.);
+            }
+        }
+        assert!(starts.iter().all(|&count| (70..130).contains(&count)));
+    }
+
+    #[test]
+    fn reads_files() {
+        let directory = std::env::temp_dir();
+        let csv = directory.join(format!("trace_test_{}.csv", std::process::id()));
+        let binary = directory.join(format!("trace_test_{}.bin", std::process::id()));
+
+        std::fs::write(&csv, "x\n0.5\n1e-3\n\n2\n").unwrap();
+        let trace = Trace::<f64>::from_csv(&csv, Replay::Loop).unwrap();
+        assert_eq!(trace.values(), [0.5, 1e-3, 2.]);
+
+        std::fs::write(&csv, "x\n0.5\nnan?\n").unwrap();
+        let error = Trace::<f64>::from_csv(&csv, Replay::Loop).err().unwrap();
+        assert!(matches!(error, TraceError::Parse { line: 3 }));
+
+        write_binary_trace(&binary, &[0.25, -1., 3.5]).unwrap();
+        let trace = Trace::<f64>::from_binary(&binary, Replay::Loop).unwrap();
+        assert_eq!(trace.values(), [0.25, -1., 3.5]);
+
+        std::fs::write(&binary, [0; 12]).unwrap();
+        let error = Trace::<f64>::from_binary(&binary, Replay::Loop)
+            .err()
+            .unwrap();
+        assert!(matches!(error, TraceError::Truncated));
+
+        std::fs::write(&binary, [0_u8; 0]).unwrap();
+        let error = Trace::<f64>::from_binary(&binary, Replay::Loop)
+            .err()
+            .unwrap();
+        assert!(matches!(error, TraceError::Empty));
+
+        std::fs::remove_file(csv).unwrap();
+        std::fs::remove_file(binary).unwrap();
+    }
+}
@@ -0,0 +1,577 @@
+use core::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use num::Float;
+
+use crate::distributions::{
+    Distribution, Exponential, Moments, ParetoII, Shifted, StandardNormal,
+    StandardUniformClosedOpen, StandardUniformOpenClosed,
+};
+use crate::rngs::Rng;
+
+use super::diffusion::poisson;
+use super::{Stateful, TimeSeries};
+
+/// Tail index of a period distribution, alpha with P(X > x) ~ x^(-alpha),
+/// infinite for light tails.
+pub trait TailIndex<F> {
+    fn tail_index(&self) -> F;
+}
+
+impl<F> TailIndex<F> for ParetoII<F>
+where
+    F: Float,
+{
+    fn tail_index(&self) -> F {
+        self.shape()
+    }
+}
+
+// Pareto-I periods are shifted Pareto-II ones,
+// `Shifted::new(ParetoII::new(alpha, xm), xm)`
+impl<D, F> TailIndex<F> for Shifted<D, F>
+where
+    F: Float,
+    D: TailIndex<F>,
+{
+    fn tail_index(&self) -> F {
+        self.distribution().tail_index()
+    }
+}
+
+impl<F> TailIndex<F> for Exponential<F>
+where
+    F: Float,
+{
+    fn tail_index(&self) -> F {
+        F::INFINITY
+    }
+}
+
+/// Distribution of the time left in a period seen from a random instant,
+/// P(R > x) = integral from x of P(X > y) dy / E[X], used to start the
+/// sources in steady state.
+pub trait Equilibrium<F> {
+    type Residual: Distribution<F>;
+
+    fn residual(&self) -> Self::Residual;
+}
+
+// for shape > 1, the residual has shape - 1 and the same scale
+impl<F> Equilibrium<F> for ParetoII<F>
+where
+    F: Float,
+    StandardUniformClosedOpen: Distribution<F>,
+{
+    type Residual = ParetoII<F>;
+
+    fn residual(&self) -> ParetoII<F> {
+        ParetoII::new(self.shape() - F::ONE, self.scale())
+    }
+}
+
+impl<F> Equilibrium<F> for Exponential<F>
+where
+    F: Float,
+    Exponential<F>: Distribution<F>,
+{
+    type Residual = Exponential<F>;
+
+    fn residual(&self) -> Exponential<F> {
+        Exponential::new(self.mean())
+    }
+}
+
+// the period lasts at least `shift`, so P(X > x) = 1 below it and the
+// residual is uniform on [0, shift) with probability shift / E[X], shift
+// plus the residual of the unshifted period otherwise
+impl<D, F> Equilibrium<F> for Shifted<D, F>
+where
+    F: Float,
+    D: Moments<F> + Equilibrium<F>,
+    StandardUniformClosedOpen: Distribution<F>,
+{
+    type Residual = ShiftedResidual<D::Residual, F>;
+
+    fn residual(&self) -> Self::Residual {
+        let shift = self.shift();
+        assert!(shift >= F::ZERO, "the shift should be non-negative");
+        ShiftedResidual {
+            residual: self.distribution().residual(),
+            shift,
+            uniform: shift / (shift + self.distribution().mean()),
+        }
+    }
+}
+
+/// Equilibrium residual of a `Shifted` period.
+#[derive(Clone)]
+pub struct ShiftedResidual<D, F> {
+    residual: D,
+    shift: F,
+    // probability that the residual is uniform on [0, shift)
+    uniform: F,
+}
+
+impl<D, F> Distribution<F> for ShiftedResidual<D, F>
+where
+    F: Float,
+    D: Distribution<F>,
+    StandardUniformClosedOpen: Distribution<F>,
+{
+    fn sample<R>(&self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let u: F = rng.sample(&StandardUniformClosedOpen);
+        if u < self.uniform {
+            // u / uniform is uniform on [0, 1) given u < uniform
+            u / self.uniform * self.shift
+        } else {
+            self.shift + rng.sample(&self.residual)
+        }
+    }
+}
+
+// the next switch of a source, ordered by time
+#[derive(Clone)]
+struct Switch<F> {
+    time: F,
+    source: usize,
+}
+
+impl<F> PartialEq for Switch<F>
+where
+    F: Float,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<F> Eq for Switch<F> where F: Float {}
+
+impl<F> PartialOrd for Switch<F>
+where
+    F: Float,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F> Ord for Switch<F>
+where
+    F: Float,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.time
+            .total_cmp(&other.time)
+            .then(self.source.cmp(&other.source))
+    }
+}
+
+/// Workload per time slot of independent on/off sources, each sending at
+/// `rate` while on. With on or off periods of tail index 1 < alpha < 2 the
+/// workload is asymptotically self-similar with H = (3 - alpha) / 2
+/// (Taqqu, Willinger and Sherman).
+///
+/// The switches of the sources are kept in a heap, so a slot costs
+/// O(log N) per switch whatever the number of sources. Unless
+/// `init_stationary` is called, every source starts at the beginning of an
+/// off period. `PacketCounts` turns the workload into packet arrivals.
+#[derive(Clone)]
+pub struct SuperposedOnOff<F, On, Off> {
+    on: On,
+    off: Off,
+    rate: F,
+    slot: F,
+    sources: usize,
+    is_on: Vec<bool>,
+    switches: BinaryHeap<Reverse<Switch<F>>>,
+    active: usize,
+    time: F,
+}
+
+impl<F, On, Off> SuperposedOnOff<F, On, Off>
+where
+    F: Float,
+    On: Distribution<F>,
+    Off: Distribution<F>,
+{
+    pub fn new(sources: usize, on: On, off: Off, rate: F, slot: F) -> Self {
+        assert!(sources >= 1, "there should be at least one source");
+        assert!(rate > F::ZERO, "the rate should be positive");
+        assert!(slot > F::ZERO, "the slot should be positive");
+        Self {
+            on,
+            off,
+            rate,
+            slot,
+            sources,
+            is_on: vec![false; sources],
+            switches: BinaryHeap::with_capacity(sources),
+            active: 0,
+            time: F::ZERO,
+        }
+    }
+
+    pub fn sources(&self) -> usize {
+        self.sources
+    }
+
+    // number of sources on at the end of the last slot
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    // H = (3 - alpha) / 2 with alpha the smaller tail index, 1/2 for
+    // alpha >= 2 (short-range dependence)
+    pub fn hurst(&self) -> F
+    where
+        On: TailIndex<F>,
+        Off: TailIndex<F>,
+    {
+        let alpha = self.on.tail_index().min(self.off.tail_index());
+        if alpha >= F::from(2.) {
+            F::from(0.5)
+        } else {
+            F::from(0.5) * (F::from(3.) - alpha)
+        }
+    }
+
+    // rate times the mean number of sources on
+    pub fn mean(&self) -> F
+    where
+        On: Moments<F>,
+        Off: Moments<F>,
+    {
+        let on = self.on.mean();
+        let fraction = on / (on + self.off.mean());
+        self.rate * self.slot * F::from(self.sources as f64) * fraction
+    }
+
+    // each source is on with probability E[on] / (E[on] + E[off]) and the
+    // time left in its period follows the equilibrium distribution, so the
+    // workload is stationary from the first slot
+    pub fn init_stationary<R>(&mut self, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+        On: Moments<F> + Equilibrium<F>,
+        Off: Moments<F> + Equilibrium<F>,
+        StandardUniformClosedOpen: Distribution<F>,
+    {
+        let on = self.on.mean();
+        let fraction = on / (on + self.off.mean());
+        let (on_residual, off_residual) = (self.on.residual(), self.off.residual());
+
+        self.switches.clear();
+        self.active = 0;
+        for source in 0..self.sources {
+            let u: F = rng.sample(&StandardUniformClosedOpen);
+            let is_on = u < fraction;
+            let left = if is_on {
+                self.active += 1;
+                rng.sample(&on_residual)
+            } else {
+                rng.sample(&off_residual)
+            };
+            self.is_on[source] = is_on;
+            self.switches.push(Reverse(Switch {
+                time: self.time + left,
+                source,
+            }));
+        }
+    }
+}
+
+impl<F, On, Off> TimeSeries<F> for SuperposedOnOff<F, On, Off>
+where
+    F: Float,
+    On: Distribution<F>,
+    Off: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        if self.switches.is_empty() {
+            for source in 0..self.sources {
+                let time = self.time + rng.sample(&self.off);
+                self.switches.push(Reverse(Switch { time, source }));
+            }
+        }
+
+        // time spent on by all the sources within the slot
+        let end = self.time + self.slot;
+        let mut busy = F::ZERO;
+        let mut last = self.time;
+        while let Some(Reverse(switch)) = self.switches.peek() {
+            if switch.time >= end {
+                break;
+            }
+            let Reverse(Switch { time, source }) = self.switches.pop().unwrap();
+            busy += F::from(self.active as f64) * (time - last);
+            last = time;
+
+            let period = if self.is_on[source] {
+                self.active -= 1;
+                rng.sample(&self.off)
+            } else {
+                self.active += 1;
+                rng.sample(&self.on)
+            };
+            self.is_on[source] = !self.is_on[source];
+            self.switches.push(Reverse(Switch {
+                time: time + period,
+                source,
+            }));
+        }
+        busy += F::from(self.active as f64) * (end - last);
+
+        self.time = end;
+        self.rate * busy
+    }
+}
+
+// the time, and for every source whether it is on and the time of its next
+// switch, none before the first slot
+impl<F, On, Off> Stateful for SuperposedOnOff<F, On, Off>
+where
+    F: Float,
+{
+    type State = (F, Vec<(bool, F)>);
+
+    fn state(&self) -> Self::State {
+        let mut sources = Vec::with_capacity(self.switches.len());
+        for Reverse(switch) in &self.switches {
+            sources.push((switch.source, switch.time));
+        }
+        sources.sort_by_key(|&(source, _)| source);
+        let sources = sources
+            .into_iter()
+            .map(|(source, time)| (self.is_on[source], time))
+            .collect();
+        (self.time, sources)
+    }
+
+    fn set_state(&mut self, (time, sources): Self::State) {
+        assert!(
+            sources.is_empty() || sources.len() == self.sources,
+            "the state should have one switch per source"
+        );
+        self.time = time;
+        self.switches.clear();
+        self.is_on.fill(false);
+        self.active = 0;
+        for (source, (is_on, time)) in sources.into_iter().enumerate() {
+            self.is_on[source] = is_on;
+            self.active += usize::from(is_on);
+            self.switches.push(Reverse(Switch { time, source }));
+        }
+    }
+
+    fn reset(&mut self) {
+        self.set_state((F::ZERO, Vec::new()));
+    }
+}
+
+/// Packet arrivals per time slot of superposed on/off sources, each sending
+/// packets as a Poisson process of intensity `rate` while on. Given the
+/// workload W of a slot, the count is Poisson with mean W, so the counts
+/// have the mean and the long-range dependence of the workload.
+#[derive(Clone)]
+pub struct PacketCounts<F, On, Off> {
+    traffic: SuperposedOnOff<F, On, Off>,
+}
+
+impl<F, On, Off> PacketCounts<F, On, Off> {
+    pub fn new(traffic: SuperposedOnOff<F, On, Off>) -> Self {
+        Self { traffic }
+    }
+
+    pub fn traffic(&self) -> &SuperposedOnOff<F, On, Off> {
+        &self.traffic
+    }
+
+    pub fn traffic_mut(&mut self) -> &mut SuperposedOnOff<F, On, Off> {
+        &mut self.traffic
+    }
+}
+
+impl<F, On, Off> TimeSeries<F> for PacketCounts<F, On, Off>
+where
+    F: Float,
+    On: Distribution<F>,
+    Off: Distribution<F>,
+    StandardNormal: Distribution<F>,
+    StandardUniformOpenClosed: Distribution<F>,
+{
+    fn get_next<R>(&mut self, rng: &mut R) -> F
+    where
+        R: Rng + ?Sized,
+    {
+        let workload = self.traffic.get_next(rng);
+        poisson(workload, rng)
+    }
+}
+
+impl<F, On, Off> Stateful for PacketCounts<F, On, Off>
+where
+    F: Float,
+{
+    type State = (F, Vec<(bool, F)>);
+
+    fn state(&self) -> Self::State {
+        self.traffic.state()
+    }
+
+    fn set_state(&mut self, state: Self::State) {
+        self.traffic.set_state(state);
+    }
+
+    fn reset(&mut self) {
+        self.traffic.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{aggregated_variance, dfa};
+    use crate::rngs::Xoshiro256PlusPlus;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    // Pareto-II periods of mean 1
+    fn pareto(alpha: f64) -> ParetoII<f64> {
+        ParetoII::new(alpha, alpha - 1.)
+    }
+
+    #[test]
+    fn stationary_workload() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut traffic = SuperposedOnOff::new(200, pareto(1.5), Exponential::new(3.), 2., 0.5);
+        assert_eq!(traffic.hurst(), 0.75);
+        assert_eq!(traffic.mean(), 50.);
+        traffic.init_stationary(&mut rng);
+
+        // the first slot already has the stationary mean
+        let mut first = 0.;
+        for _ in 0..2000 {
+            traffic.init_stationary(&mut rng);
+            first += rng.get_next(&mut traffic);
+        }
+        assert!((first / 2000. / 50. - 1.).abs() < 0.02);
+
+        let n = 100000;
+        let total = (0..n).map(|_| rng.get_next(&mut traffic)).sum::<f64>();
+        assert!((total / n as f64 / 50. - 1.).abs() < 0.05);
+        assert!(traffic.active() <= traffic.sources());
+    }
+
+    #[test]
+    fn state_forks_and_resets() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut traffic = SuperposedOnOff::new(50, pareto(1.5), pareto(1.2), 1., 1.);
+        let first = (0..100)
+            .map(|_| rng.get_next(&mut traffic))
+            .collect::<Vec<f64>>();
+
+        let state = traffic.state();
+        let active = traffic.active();
+        let mut fork_rng = rng.clone();
+        let path = (0..100)
+            .map(|_| rng.get_next(&mut traffic))
+            .collect::<Vec<f64>>();
+        traffic.set_state(state);
+        assert_eq!(traffic.active(), active);
+        for &x in &path {
+            assert_eq!(fork_rng.get_next(&mut traffic), x);
+        }
+
+        traffic.reset();
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        for &x in &first {
+            assert_eq!(rng.get_next(&mut traffic), x);
+        }
+    }
+
+    #[test]
+    fn light_tails_are_short_range_dependent() {
+        let traffic = SuperposedOnOff::new(10, Exponential::new(1.), pareto(2.5), 1., 1.);
+        assert_eq!(traffic.hurst(), 0.5);
+    }
+
+    #[test]
+    fn pareto_i_periods() {
+        // Pareto-I of minimum 1 and alpha 1.5, of mean 3
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let pareto_i = Shifted::new(ParetoII::new(1.5, 1.), 1.);
+
+        // P(R < 1) = 1 / E[X], the period lasting at least 1
+        let residual = pareto_i.residual();
+        let n = 100000;
+        let below = (0..n)
+            .filter(|_| {
+                let r: f64 = rng.sample(&residual);
+                r < 1.
+            })
+            .count();
+        assert!((below as f64 / n as f64 - 1. / 3.).abs() < 0.01);
+
+        let mut traffic = SuperposedOnOff::new(100, pareto_i, Exponential::new(3.), 1., 1.);
+        assert!((traffic.hurst() - 0.75).abs() < 1e-12);
+        assert_eq!(traffic.mean(), 50.);
+
+        let mut first = 0.;
+        for _ in 0..2000 {
+            traffic.init_stationary(&mut rng);
+            first += rng.get_next(&mut traffic);
+        }
+        assert!((first / 2000. / 50. - 1.).abs() < 0.02);
+    }
+
+    #[test]
+    fn packet_counts() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let traffic = SuperposedOnOff::new(200, pareto(1.5), Exponential::new(3.), 2., 0.5);
+        let mut packets = PacketCounts::new(traffic);
+        packets.traffic_mut().init_stationary(&mut rng);
+
+        let n = 100000;
+        let data = (0..n)
+            .map(|_| rng.get_next(&mut packets))
+            .collect::<Vec<f64>>();
+        assert!(data.iter().all(|&x| x >= 0. && x == x.floor()));
+        let mean = data.iter().sum::<f64>() / n as f64;
+        assert!((mean / packets.traffic().mean() - 1.).abs() < 0.05);
+    }
+
+    #[test]
+    fn hurst_matches_estimates() {
+        // periods of mean 10 seen through slots of 10, so that the short-range
+        // dependence within a period does not dominate; the Whittle estimator
+        // is biased here, the spectrum not being that of fGn at high
+        // frequencies
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let (on, off) = (ParetoII::new(1.4, 4.), ParetoII::new(1.4, 4.));
+        let mut traffic = SuperposedOnOff::new(200, on, off, 1., 10.);
+        traffic.init_stationary(&mut rng);
+
+        let data = (0..1 << 14)
+            .map(|_| rng.get_next(&mut traffic))
+            .collect::<Vec<f64>>();
+        let hurst = traffic.hurst();
+        assert!((hurst - 0.8).abs() < 1e-12);
+        for (estimate, tolerance) in [(aggregated_variance(&data), 0.05), (dfa(&data), 0.1)] {
+            let estimate = estimate.unwrap().hurst;
+            assert!((estimate - hurst).abs() < tolerance, "{estimate} {hurst}");
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::rngs::Rng;
+
+/// Complements every bit produced by the wrapped generator, so a uniform `u`
+/// becomes `1 - u`.
+///
+/// The inverse CDF samplers are monotone in `u`, so a replication driven by
+/// `Antithetic::new(rng.clone())` is negatively correlated with the one
+/// driven by `rng`. This only holds for outputs monotone in the uniforms:
+/// `StandardNormal` uses Box-Muller, where `1 - u` keeps the sign of the
+/// cosine and only changes the radius, so its antithetic normals are
+/// positively correlated. Draw normals through `Quantile` instead, e.g.
+/// `normal.quantile(rng.sample(&StandardUniformOpenOpen))`.
+pub struct Antithetic<R> {
+    rng: R,
+}
+
+impl<R> Antithetic<R>
+where
+    R: Rng,
+{
+    pub fn new(rng: R) -> Self {
+        Self { rng }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R> Rng for Antithetic<R>
+where
+    R: Rng,
+{
+    // for the standard uniforms this maps [0, 1) onto (0, 1] and vice versa,
+    // e.g. StandardUniformOpenClosed(!x) = 1 - StandardUniformClosedOpen(x)
+    fn fill_bytes(&mut self, buf: &mut [u8]) {
+        self.rng.fill_bytes(buf);
+        for b in buf {
+            *b = !*b;
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod antithetic;
+mod paired;
+
+pub use antithetic::Antithetic;
+pub use paired::{paired_replications, Paired};
@@ -0,0 +1,143 @@
+use num::Float;
+
+/// Sample statistics of `n` paired replications `(a, b)`.
+///
+/// For antithetic pairs the estimator of interest is the pair average, for
+/// common random numbers it is the difference between the two scenarios.
+/// The variance reductions compare against `n` independent pairs.
+pub struct Paired<F> {
+    pub count: usize,
+    pub mean_a: F,
+    pub mean_b: F,
+    pub variance_a: F,
+    pub variance_b: F,
+    pub covariance: F,
+}
+
+impl<F> Paired<F>
+where
+    F: Float,
+{
+    pub fn correlation(&self) -> F {
+        self.covariance / (self.variance_a * self.variance_b).sqrt()
+    }
+
+    pub fn average_mean(&self) -> F {
+        (self.mean_a + self.mean_b) / F::from(2.0)
+    }
+
+    // variance of the estimator of the average mean
+    pub fn average_variance(&self) -> F {
+        let v = self.variance_a + self.variance_b + F::from(2.0) * self.covariance;
+        v / F::from(4.0) / F::from(self.count as f64)
+    }
+
+    // Var(a) + Var(b) over Var(a) + Var(b) + 2 Cov(a, b)
+    pub fn average_variance_reduction(&self) -> F {
+        let independent = self.variance_a + self.variance_b;
+        independent / (independent + F::from(2.0) * self.covariance)
+    }
+
+    pub fn difference_mean(&self) -> F {
+        self.mean_a - self.mean_b
+    }
+
+    // variance of the estimator of the difference mean
+    pub fn difference_variance(&self) -> F {
+        let v = self.variance_a + self.variance_b - F::from(2.0) * self.covariance;
+        v / F::from(self.count as f64)
+    }
+
+    // Var(a) + Var(b) over Var(a) + Var(b) - 2 Cov(a, b)
+    pub fn difference_variance_reduction(&self) -> F {
+        let independent = self.variance_a + self.variance_b;
+        independent / (independent - F::from(2.0) * self.covariance)
+    }
+}
+
+// runs `count` replications, `replicate(i)` should return the outputs of the
+// i-th pair, e.g. a scenario driven by `rng` and the same scenario driven by
+// `Antithetic::new(rng)`, or two scenarios driven by the same streams
+pub fn paired_replications<F, G>(count: usize, mut replicate: G) -> Paired<F>
+where
+    F: Float,
+    G: FnMut(usize) -> (F, F),
+{
+    assert!(count > 1, "at least two replications are needed");
+
+    // Welford's online algorithm
+    let mut mean_a = F::ZERO;
+    let mut mean_b = F::ZERO;
+    let mut m2_a = F::ZERO;
+    let mut m2_b = F::ZERO;
+    let mut c = F::ZERO;
+
+    for i in 0..count {
+        let (a, b) = replicate(i);
+        let n = F::from((i + 1) as f64);
+
+        let da = a - mean_a;
+        let db = b - mean_b;
+        mean_a += da / n;
+        mean_b += db / n;
+        m2_a += da * (a - mean_a);
+        m2_b += db * (b - mean_b);
+        c += da * (b - mean_b);
+    }
+
+    let n1 = F::from((count - 1) as f64);
+
+    Paired {
+        count,
+        mean_a,
+        mean_b,
+        variance_a: m2_a / n1,
+        variance_b: m2_b / n1,
+        covariance: c / n1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributions::{Exponential, StandardUniformClosedOpen, StandardUniformOpenClosed};
+    use crate::rngs::{Rng, Xoshiro256PlusPlus};
+    use crate::variance::Antithetic;
+
+    const SEED: [u64; 4] = [
+        0xce124f618403c393,
+        0x28d53c991db633b3,
+        0x84e1e11761ad8d8f,
+        0x3d51155d3a5e4243,
+    ];
+
+    #[test]
+    fn antithetic_complements_uniforms() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let mut anti = Antithetic::new(rng.clone());
+
+        for _ in 0..1000 {
+            let u: f64 = rng.sample(&StandardUniformClosedOpen);
+            let v: f64 = anti.sample(&StandardUniformOpenClosed);
+            assert_eq!(u + v, 1.0);
+        }
+    }
+
+    #[test]
+    fn antithetic_reduces_variance() {
+        let mut rng = Xoshiro256PlusPlus::new(SEED);
+        let exponential = Exponential::new(1.0);
+
+        let paired = paired_replications(1000, |_| {
+            let mut anti = Antithetic::new(rng.clone());
+            let a: f64 = (0..10).map(|_| rng.sample(&exponential)).sum();
+            let b: f64 = (0..10).map(|_| anti.sample(&exponential)).sum();
+            rng.jump();
+            (a, b)
+        });
+
+        assert!(paired.correlation() < -0.5);
+        assert!(paired.average_variance_reduction() > 2.0);
+        assert!((paired.average_mean() - 10.0).abs() < 0.2);
+    }
+}
@@ -9,7 +9,6 @@ const SEED: [u64; 4] = [
     0x7bacd1bfe56ae9f5,
 ];
 
-const WARMUP_COUNT: usize = 10000;
 const DATA_COUNT: usize = 100000;
 
 fn main() {
@@ -20,11 +19,11 @@ fn main() {
         let theta = [-0.3];
         let std_dev = 3_f64.sqrt();
         let mean = 5.;
-        rand::series::Arma::new(phi, theta, std_dev, mean)
+        rand::series::Arma::new(phi, theta, std_dev, mean).unwrap()
     };
+    arma.init_stationary(&mut rng).unwrap();
 
     let data = std::iter::repeat_with(|| rng.get_next(&mut arma))
-        .skip(WARMUP_COUNT)
         .take(DATA_COUNT)
         .collect::<Vec<_>>();
 
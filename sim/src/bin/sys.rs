@@ -1,50 +1,41 @@
 use std::fmt::Write;
-use std::{cell::RefCell, rc::Rc};
 
-use queue::system::QueueSystem;
+use queue::system::{from_series, QueueSystem};
 use rand::rngs::Rng;
+use rand::series::{Arma, FromDistribution, TimeSeries};
+use sim::arrival_model;
 
-fn get_system<R: Rng + 'static>(rng: &Rc<RefCell<R>>, alpha: f64) -> impl QueueSystem<f64> {
-    let arrival_interval = {
-        let mut arma = {
-            let phi = [0.7];
-            let theta = [-0.3];
-            let std_dev = 3_f64.sqrt();
-            let mean = 5.;
-            rand::series::Arma::new(phi, theta, std_dev, mean)
-        };
-
-        let rng = rng.clone();
-        move || rng.borrow_mut().get_next(&mut arma).abs() * alpha
-    };
+// each random input draws from its own stream, so every alpha sees the same
+// random inputs (common random numbers)
+fn get_system<R: Rng + 'static>(
+    rngs: [R; 3],
+    arma: Arma<f64, 1, 1>,
+    alpha: f64,
+) -> impl QueueSystem<f64> {
+    let [arrival_rng, serving_rng_1, serving_rng_2] = rngs;
 
-    let serving_time_1 = {
-        let dist = {
-            let mean = 1.;
-            rand::distributions::Exponential::new(mean)
-        };
+    let arrival_interval = arma.map(f64::exp).scale(alpha);
 
-        let rng = rng.clone();
-        move || rng.borrow_mut().sample(&dist)
+    let serving_time_1 = {
+        let mean = 1.;
+        FromDistribution::new(rand::distributions::Exponential::new(mean))
     };
 
     let serving_time_2 = {
-        let dist = {
-            let shape = 1.8;
+        let shape = 1.8;
 
-            let mean = 1.;
-            let scale = mean * (shape - 1.);
+        let mean = 1.;
+        let scale = mean * (shape - 1.);
 
-            rand::distributions::ParetoII::new(shape, scale)
-        };
-
-        let rng = rng.clone();
-        move || rng.borrow_mut().sample(&dist)
+        FromDistribution::new(rand::distributions::ParetoII::new(shape, scale))
     };
 
     queue::system::Fifo::new(
-        Box::new(arrival_interval),
-        [Box::new(serving_time_1), Box::new(serving_time_2)],
+        from_series(arrival_interval, arrival_rng),
+        [
+            from_series(serving_time_1, serving_rng_1),
+            from_series(serving_time_2, serving_rng_2),
+        ],
     )
 }
 
@@ -72,17 +63,20 @@ fn main() {
         .unwrap();
     }
 
+    // configured once, each simulation starting from a fresh copy
+    let arma = arrival_model();
+
     for i in 0..SIMULATION_COUNT {
         for mult in 1..=10 {
             let mut rng = rand::rngs::Xoshiro256Plus::new(SEED);
             for _ in 0..i {
-                rng.jump();
+                rng.long_jump();
             }
-            let rng = Rc::new(RefCell::new(rng));
+            let rngs = rand::rngs::streams(rng);
 
             let alpha = 0.1 * mult as f64;
 
-            let mut system = get_system(&rng, alpha);
+            let mut system = get_system(rngs, arma.clone(), alpha);
 
             let queue_times = std::iter::repeat_with(|| {
                 let client = system.step();
@@ -0,0 +1,119 @@
+use std::fmt::Write;
+
+use queue::system::{from_series, QueueSystem};
+use rand::rngs::Rng;
+use rand::series::{FractionalGaussianNoise, FromDistribution, TimeSeries};
+use sim::arrival_model;
+
+// mean and variance of the ln of the interarrival times of sys.rs
+fn log_moments() -> (f64, f64) {
+    let arma = arrival_model();
+    (arma.mean(), arma.variance())
+}
+
+// the circulant embedding is the costly part, so it is built once per Hurst
+// exponent and cloned for every simulation
+fn get_noise(hurst: f64) -> FractionalGaussianNoise<f64> {
+    let (_, log_variance) = log_moments();
+
+    // one exact block per simulation
+    FractionalGaussianNoise::new(hurst, log_variance.sqrt(), WARMUP_COUNT + DATA_COUNT)
+}
+
+// same system as sys.rs, the log-interarrival times being fractional
+// Gaussian noise instead of ARMA(1, 1), with the same mean and variance
+fn get_system<R: Rng + 'static>(
+    rngs: [R; 3],
+    fgn: FractionalGaussianNoise<f64>,
+) -> impl QueueSystem<f64> {
+    let [arrival_rng, serving_rng_1, serving_rng_2] = rngs;
+
+    let arrival_interval = {
+        let (log_mean, _) = log_moments();
+        fgn.shift(log_mean).map(f64::exp).scale(ALPHA)
+    };
+
+    let serving_time_1 = {
+        let mean = 1.;
+        FromDistribution::new(rand::distributions::Exponential::new(mean))
+    };
+
+    let serving_time_2 = {
+        let shape = 1.8;
+
+        let mean = 1.;
+        let scale = mean * (shape - 1.);
+
+        FromDistribution::new(rand::distributions::ParetoII::new(shape, scale))
+    };
+
+    queue::system::Fifo::new(
+        from_series(arrival_interval, arrival_rng),
+        [
+            from_series(serving_time_1, serving_rng_1),
+            from_series(serving_time_2, serving_rng_2),
+        ],
+    )
+}
+
+const SEED: [u64; 4] = [
+    0x5ac6b27ff90c4d13,
+    0x63dc705cd7f0559b,
+    0x323c660b0356facf,
+    0x7bacd1bfe56ae9f5,
+];
+
+const ALPHA: f64 = 0.3;
+const HURST: [f64; 5] = [0.5, 0.6, 0.7, 0.8, 0.9];
+
+const SIMULATION_COUNT: usize = 100;
+const WARMUP_COUNT: usize = 10000;
+const DATA_COUNT: usize = 100000;
+
+fn main() {
+    let mut output = String::new();
+
+    for (j, hurst) in HURST.iter().enumerate() {
+        write!(
+            &mut output,
+            "queue_time_hurst{:02}{}",
+            (hurst * 100.) as u32,
+            if j + 1 < HURST.len() { "," } else { "\n" },
+        )
+        .unwrap();
+    }
+
+    let noises = HURST.map(get_noise);
+
+    for i in 0..SIMULATION_COUNT {
+        for (j, noise) in noises.iter().enumerate() {
+            let mut rng = rand::rngs::Xoshiro256Plus::new(SEED);
+            for _ in 0..i {
+                rng.long_jump();
+            }
+            let rngs = rand::rngs::streams(rng);
+
+            let mut system = get_system(rngs, noise.clone());
+
+            let queue_times = std::iter::repeat_with(|| {
+                let client = system.step();
+                client.serve_instant - client.arrival_instant
+            })
+            .skip(WARMUP_COUNT)
+            .take(DATA_COUNT)
+            .collect::<Vec<_>>();
+
+            let average_queue_time = queue_times.iter().sum::<f64>() / DATA_COUNT as f64;
+
+            write!(
+                &mut output,
+                "{}{}",
+                average_queue_time,
+                if j + 1 < HURST.len() { "," } else { "\n" },
+            )
+            .unwrap();
+        }
+    }
+
+    std::fs::write("sys_fgn.csv", output).unwrap();
+}
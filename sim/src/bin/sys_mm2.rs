@@ -1,18 +1,16 @@
 use std::fmt::Write;
-use std::{cell::RefCell, rc::Rc};
 
 use queue::system::QueueSystem;
 use rand::rngs::Rng;
 
-fn get_system<R: Rng + 'static>(rng: &Rc<RefCell<R>>, alpha: f64) -> impl QueueSystem<f64> {
-    let arrival_interval = {
-        let dist = {
-            let mean = 5.;
-            rand::distributions::Exponential::new(mean)
-        };
+// each random input draws from its own stream, so every alpha sees the same
+// random inputs (common random numbers)
+fn get_system<R: Rng + 'static>(rngs: [R; 3], alpha: f64) -> impl QueueSystem<f64> {
+    let [arrival_rng, serving_rng_1, serving_rng_2] = rngs;
 
-        let rng = rng.clone();
-        move || rng.borrow_mut().sample(&dist) * alpha
+    let arrivals = {
+        let mean = 5. * alpha;
+        rand::series::Renewal::new(rand::distributions::Exponential::new(mean))
     };
 
     let serving_time_1 = {
@@ -21,8 +19,8 @@ fn get_system<R: Rng + 'static>(rng: &Rc<RefCell<R>>, alpha: f64) -> impl QueueS
             rand::distributions::Exponential::new(mean)
         };
 
-        let rng = rng.clone();
-        move || rng.borrow_mut().sample(&dist)
+        let mut rng = serving_rng_1;
+        move || rng.sample(&dist)
     };
 
     let serving_time_2 = {
@@ -31,12 +29,13 @@ fn get_system<R: Rng + 'static>(rng: &Rc<RefCell<R>>, alpha: f64) -> impl QueueS
             rand::distributions::Exponential::new(mean)
         };
 
-        let rng = rng.clone();
-        move || rng.borrow_mut().sample(&dist)
+        let mut rng = serving_rng_2;
+        move || rng.sample(&dist)
     };
 
-    queue::system::Fifo::new(
-        Box::new(arrival_interval),
+    queue::system::Fifo::with_arrivals(
+        arrivals,
+        arrival_rng,
         [Box::new(serving_time_1), Box::new(serving_time_2)],
     )
 }
@@ -69,13 +68,13 @@ fn main() {
         for mult in 1..=10 {
             let mut rng = rand::rngs::Xoshiro256Plus::new(SEED);
             for _ in 0..i {
-                rng.jump();
+                rng.long_jump();
             }
-            let rng = Rc::new(RefCell::new(rng));
+            let rngs = rand::rngs::streams(rng);
 
             let alpha = 0.1 * mult as f64;
 
-            let mut system = get_system(&rng, alpha);
+            let mut system = get_system(rngs, alpha);
 
             let queue_times = std::iter::repeat_with(|| {
                 let client = system.step();
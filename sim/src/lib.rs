@@ -0,0 +1,14 @@
+use rand::series::Arma;
+
+// interarrival times of the sys experiments before scaling by alpha: a
+// log-ARMA, positive intervals with the mean and variance of an ARMA(1, 1)
+// with innovation variance 3 and mean 5
+pub fn arrival_model() -> Arma<f64, 1, 1> {
+    let phi = [0.7];
+    let theta = [-0.3];
+    let mean = 5.;
+    let variance = Arma::new(phi, theta, 3_f64.sqrt(), mean)
+        .unwrap()
+        .variance();
+    Arma::log_normal(phi, theta, mean, variance).unwrap()
+}